@@ -1,6 +1,6 @@
 /* @ts-self-types="./biome_fmt_web.d.ts" */
 import * as import_bg from "./biome_fmt_bg.js";
-const { __wbg_set_wasm, format, formatRange, ...wasmImport } = import_bg;
+const { __wbg_set_wasm, format, formatRange, formatRanges, formatWithCursor, formatEdits, check, ...wasmImport } = import_bg;
 
 function getImports() {
 	return {
@@ -90,4 +90,4 @@ export default async function initAsync(init_input) {
 	return finalize_init(instance, module);
 }
 
-export { format, formatRange };
+export { format, formatRange, formatRanges, formatWithCursor, formatEdits, check };
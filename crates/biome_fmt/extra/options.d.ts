@@ -1,4 +1,7 @@
 import type { LayoutConfig } from "./layout_config.d.ts";
+import type { SortImportsOptions } from "./sort_imports.d.ts";
+
+export type * from "./sort_imports.d.ts";
 
 /**
  * Configuration options for Biome formatter.
@@ -38,4 +41,7 @@ export interface Config extends LayoutConfig {
 
 	/** When formatting binary expressions, whether to break the line before or after the operator. Defaults to "after". */
 	operatorLinebreak?: "before" | "after";
+
+	/** Sort import statements before formatting. By default disabled. Also accepted as `organizeImports`. */
+	sortImports?: SortImportsOptions;
 }
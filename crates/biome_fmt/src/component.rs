@@ -0,0 +1,51 @@
+//! The WebAssembly component, exporting the `formatter` interface of the WIT world in `wit/`.
+
+use common::component::{parse_config, types};
+
+use crate::{check_script_with_config, format_script_range_with_config, format_script_with_config};
+
+wit_bindgen::generate!({
+    world: "biome-formatter",
+    path: "../../wit",
+    with: { "wasm-fmt:formatter/types": common::component::types },
+});
+
+struct Component;
+
+impl exports::wasm_fmt::formatter::formatter::Guest for Component {
+    fn format(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<String, String> {
+        format_script_with_config(&src, &filename, parse_config(config.as_ref())?)
+    }
+
+    fn format_range(
+        src: String,
+        filename: String,
+        range: types::TextRange,
+        config: Option<types::Config>,
+    ) -> Result<types::PrintedRange, String> {
+        let printed = format_script_range_with_config(
+            &src,
+            range.into(),
+            &filename,
+            parse_config(config.as_ref())?,
+        )?;
+
+        Ok(printed.into())
+    }
+
+    fn check(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<types::CheckResult, String> {
+        let result = check_script_with_config(&src, &filename, parse_config(config.as_ref())?)?;
+
+        Ok(result.into())
+    }
+}
+
+export!(Component);
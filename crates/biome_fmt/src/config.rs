@@ -8,7 +8,7 @@ use biome_js_formatter::context::{
 };
 use biome_js_syntax::JsFileSource;
 
-use common::LayoutConfig;
+use common::{LayoutConfig, SortImportsOptions};
 use serde::{Deserialize, Deserializer};
 use std::str::FromStr;
 
@@ -76,6 +76,10 @@ pub struct LanguageOptions {
         deserialize_with = "deserialize_option_from_str"
     )]
     operator_linebreak: Option<OperatorLinebreak>,
+
+    /// Sort import statements before formatting. By default disabled.
+    #[serde(default, alias = "sortImports", alias = "organizeImports", alias = "organize_imports")]
+    sort_imports: Option<SortImportsOptions>,
 }
 
 impl BiomeConfig {
@@ -91,6 +95,11 @@ impl BiomeConfig {
         self
     }
 
+    #[must_use]
+    pub fn sort_imports(&self) -> Option<&SortImportsOptions> {
+        self.language.sort_imports.as_ref()
+    }
+
     #[must_use]
     pub fn fill_empty_layout_with(mut self, layout: &LayoutConfig) -> Self {
         self.layout = self.layout.fill_empty_with(layout);
@@ -220,4 +229,15 @@ mod tests {
 
         assert!(config.language.quote_style.is_some());
     }
+
+    #[test]
+    fn organize_imports_alias_deserializes() {
+        let config: BiomeConfig =
+            serde_json::from_str(r#"{"organizeImports":{"groups":["builtin","external"]}}"#)
+                .unwrap();
+
+        let sort_imports = config.sort_imports().unwrap();
+        assert_eq!(sort_imports.groups.groups.len(), 2);
+        assert!(sort_imports.newlines_between);
+    }
 }
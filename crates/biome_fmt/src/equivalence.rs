@@ -0,0 +1,163 @@
+//! Checks that formatting keeps the syntax tree of scripts.
+
+use biome_js_parser::{JsParserOptions, parse};
+use biome_js_syntax::{JsSyntaxKind, JsSyntaxNode, JsSyntaxToken};
+use biome_rowan::{Direction, NodeOrToken, WalkEvent};
+use common::{SourceError, TreeToken, compare_trees, normalize_number, unquote};
+
+use crate::{BiomeConfig, sort_imports, source_type_from_filename};
+
+/// Checks that `code`, the formatted version of `src`, parses to the same syntax tree.
+///
+/// Trivia, quotes, number spelling, semicolons, commas, parentheses, including those of arrow
+/// function parameters, and the layout of JSX text are ignored. When `config` sorts imports,
+/// the source is compared with its imports sorted.
+pub fn verify_script_equivalence(
+    src: &str,
+    code: &str,
+    filename: &str,
+    config: &BiomeConfig,
+) -> Result<(), SourceError> {
+    let source_type = source_type_from_filename(filename);
+    let options = || JsParserOptions::default().with_parse_class_parameter_decorators();
+
+    let mut before = parse(src, source_type, options());
+    let sorted;
+    let mut src = src;
+    if !before.has_errors()
+        && let Some(sort_options) = config.sort_imports()
+        && let Some(sorted_src) = sort_imports::sort_imports(src, &before.tree(), sort_options)
+    {
+        sorted = sorted_src;
+        src = &sorted;
+        before = parse(src, source_type, options());
+    }
+
+    let after = parse(code, source_type, options());
+    if after.has_errors() && !before.has_errors() {
+        return Err("the formatted script has syntax errors".into());
+    }
+
+    compare_trees(
+        "script",
+        src,
+        &tree_tokens(&before.syntax()),
+        code,
+        &tree_tokens(&after.syntax()),
+    )
+}
+
+/// Flattens the tree under `root` to its node kinds and normalized tokens, in source order.
+fn tree_tokens(root: &JsSyntaxNode) -> Vec<TreeToken> {
+    let mut tokens = vec![];
+    let mut preorder = root.preorder_with_tokens(Direction::Next);
+
+    while let Some(event) = preorder.next() {
+        let WalkEvent::Enter(element) = event else { continue };
+
+        match element {
+            NodeOrToken::Node(node) => {
+                if is_removable(&node) {
+                    preorder.skip_subtree();
+                } else if !is_transparent(&node) {
+                    let offset = u32::from(node.text_trimmed_range().start());
+                    tokens.push(TreeToken::new(format!("{:?}", node.kind()), offset));
+                }
+            }
+            NodeOrToken::Token(token) => push_token(&mut tokens, &token),
+        }
+    }
+
+    tokens
+}
+
+/// Nodes the formatter may drop: empty statements and `{" "}` between JSX children.
+fn is_removable(node: &JsSyntaxNode) -> bool {
+    match node.kind() {
+        JsSyntaxKind::JS_EMPTY_STATEMENT | JsSyntaxKind::JS_EMPTY_CLASS_MEMBER => true,
+        JsSyntaxKind::JSX_EXPRESSION_CHILD => {
+            let text = node.text_trimmed().to_string();
+            let text: String = text.split_whitespace().collect();
+            text == r#"{""}"# || text == "{''}"
+        }
+        _ => false,
+    }
+}
+
+/// Nodes the formatter may add or remove around the same content.
+fn is_transparent(node: &JsSyntaxNode) -> bool {
+    let is_arrow = |depth| {
+        node.ancestors()
+            .nth(depth)
+            .is_some_and(|ancestor| ancestor.kind() == JsSyntaxKind::JS_ARROW_FUNCTION_EXPRESSION)
+    };
+
+    match node.kind() {
+        JsSyntaxKind::JS_PARENTHESIZED_EXPRESSION
+        | JsSyntaxKind::TS_PARENTHESIZED_TYPE
+        | JsSyntaxKind::JSX_TEXT => true,
+        // `(a) => a` and `a => a`
+        JsSyntaxKind::JS_PARAMETERS => is_arrow(1),
+        JsSyntaxKind::JS_PARAMETER_LIST => is_arrow(2),
+        JsSyntaxKind::JS_FORMAL_PARAMETER => is_arrow(3),
+        _ => false,
+    }
+}
+
+fn push_token(tokens: &mut Vec<TreeToken>, token: &JsSyntaxToken) {
+    let offset = u32::from(token.text_trimmed_range().start());
+    let text = token.text_trimmed();
+    let parent = token.parent();
+    let parent_kind = parent.as_ref().map(JsSyntaxNode::kind);
+
+    let text = match token.kind() {
+        JsSyntaxKind::EOF | JsSyntaxKind::SEMICOLON | JsSyntaxKind::COMMA => return,
+        JsSyntaxKind::L_PAREN | JsSyntaxKind::R_PAREN
+            if parent.as_ref().is_some_and(is_transparent) =>
+        {
+            return;
+        }
+        // The leading `|` or `&` of a union or intersection type.
+        JsSyntaxKind::PIPE | JsSyntaxKind::AMP
+            if matches!(
+                parent_kind,
+                Some(JsSyntaxKind::TS_UNION_TYPE | JsSyntaxKind::TS_INTERSECTION_TYPE)
+            ) && token.prev_sibling_or_token().is_none() =>
+        {
+            return;
+        }
+        JsSyntaxKind::JS_STRING_LITERAL | JsSyntaxKind::JSX_STRING_LITERAL => unquote(text),
+        JsSyntaxKind::JS_NUMBER_LITERAL => normalize_number(text),
+        JsSyntaxKind::JS_BIGINT_LITERAL => text.to_ascii_lowercase(),
+        JsSyntaxKind::JSX_TEXT_LITERAL => {
+            tokens.extend(text.split_whitespace().map(|word| TreeToken::new(word, offset)));
+            return;
+        }
+        _ => text.to_string(),
+    };
+
+    tokens.push(TreeToken::new(text, offset));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_unchanged_trees() {
+        let src = "const a = (1.0), b = 'x';;\nlet f = x => <p>a  {\" \"}\n b</p>\n";
+        let code = "const a = 1.0,\n  b = \"x\";\nlet f = (x) => <p>a b</p>;\n";
+        assert_eq!(verify_script_equivalence(src, code, "a.jsx", &BiomeConfig::default()), Ok(()));
+    }
+
+    #[test]
+    fn reports_changed_literals() {
+        let err =
+            verify_script_equivalence("a = 'x';", "a = \"y\";\n", "a.js", &BiomeConfig::default())
+                .unwrap_err();
+        assert!(
+            err.message.contains("`x` at 1:5 of the source became `y` at 1:5 of the output"),
+            "{err}"
+        );
+    }
+}
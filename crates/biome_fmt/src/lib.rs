@@ -1,16 +1,27 @@
+#[cfg(feature = "component")]
+mod component;
 mod config;
+mod equivalence;
+mod sort_imports;
 
+pub use common::{
+    CheckResult, FormattedRanges, FormattedWithCursor, PrintedRange, SortImportsOptions,
+    SourceError, TextEdit, TextRange,
+};
 pub use config::BiomeConfig;
 pub use config::IndentStyle;
+pub use equivalence::verify_script_equivalence;
 
+use biome_js_formatter::context::JsFormatOptions;
 use biome_js_formatter::format_node as biome_format_node;
 use biome_js_formatter::format_range as biome_format_range;
 use biome_js_parser::{JsParserOptions, parse};
 use biome_js_syntax::{
     JsFileSource, LanguageVariant, ModuleKind, TextRange as BiomeTextRange, TextSize,
 };
-use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "wasm-bindgen")]
+use common::{EncodedRange, OffsetConverter, OffsetEncoding, Position};
 #[cfg(feature = "wasm-bindgen")]
 use wasm_bindgen::prelude::*;
 
@@ -20,11 +31,14 @@ extern "C" {
     #[wasm_bindgen(typescript_type = "Config")]
     pub type Config;
 
-    #[wasm_bindgen(typescript_type = "TextRange")]
+    #[wasm_bindgen(typescript_type = "TextRange | LineColumnRange")]
     pub type JsTextRange;
 
     #[wasm_bindgen(typescript_type = "PrintedRange")]
     pub type JsPrintedRange;
+
+    #[wasm_bindgen(typescript_type = "Array<TextRange | LineColumnRange | LineRange>")]
+    pub type JsRanges;
 }
 
 #[cfg(feature = "wasm-bindgen")]
@@ -36,54 +50,46 @@ type X = "" | "x";
 
 type Filename = `index.${Mod}${"t" | "j"}s${X}` | `index.d.${Mod}ts${X}` | (string & {});
 
+import type {
+	CheckResult,
+	FormattedWithCursor,
+	LineColumn,
+	LineColumnRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./text.d.ts";
+
+export type * from "./text.d.ts";
+
 /**
- * A range in text, using UTF-8 byte offsets.
+ * A range of whole lines, 1-based and inclusive, e.g. a diff hunk.
  */
-export interface TextRange {
-	start: number;
-	end: number;
+export interface LineRange {
+	startLine: number;
+	endLine: number;
 }
 
 /**
- * Result of formatting a range of code.
+ * Result of formatting several ranges of code: the whole new code and the formatted ranges,
+ * against the offsets of the original code.
  */
-export interface PrintedRange {
+export interface FormattedRanges {
 	code: string;
-	source_range: TextRange;
+	ranges: PrintedRange[];
 }
-
 "#;
 
-#[derive(Serialize, Deserialize, Copy, Clone)]
-pub struct TextRange {
-    pub start: u32,
-    pub end: u32,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct PrintedRange {
-    pub code: String,
-    pub range: Option<TextRange>,
+fn to_biome_range(range: TextRange) -> BiomeTextRange {
+    BiomeTextRange::new(TextSize::from(range.start), TextSize::from(range.end))
 }
 
-impl From<BiomeTextRange> for TextRange {
-    fn from(range: BiomeTextRange) -> Self {
-        Self { start: range.start().into(), end: range.end().into() }
-    }
-}
-
-impl From<TextRange> for BiomeTextRange {
-    fn from(range: TextRange) -> Self {
-        BiomeTextRange::new(TextSize::from(range.start), TextSize::from(range.end))
-    }
-}
-
-impl From<biome_formatter::Printed> for PrintedRange {
-    fn from(printed: biome_formatter::Printed) -> Self {
-        let code = printed.clone().into_code();
-        let range = printed.range().map(Into::into);
-        Self { code, range }
-    }
+fn printed_range(printed: biome_formatter::Printed) -> PrintedRange {
+    let range = printed
+        .range()
+        .map(|range| TextRange { start: range.start().into(), end: range.end().into() });
+    PrintedRange { code: printed.into_code(), range }
 }
 
 /// Formats the given JavaScript/TypeScript code with the provided Configuration.
@@ -112,17 +118,24 @@ pub fn format(
 #[wasm_bindgen(js_name = formatRange)]
 pub fn format_range(
     #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to format")] src: &str,
-    #[wasm_bindgen(param_description = "The text range to format (start and end byte offsets)")]
-    range: JsTextRange,
+    #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
     #[wasm_bindgen(
         unchecked_param_type = "Filename",
         param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
     )]
     filename: &str,
     #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
 ) -> Result<JsValue, JsValue> {
-    let text_range: TextRange = serde_wasm_bindgen::from_value(range.into())
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let range: EncodedRange = serde_wasm_bindgen::from_value(range.into())
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let text_range = offsets.range_to_utf8(range)?;
 
     let config = config
         .map(|x| serde_wasm_bindgen::from_value(x.clone()))
@@ -134,26 +147,207 @@ pub fn format_range(
     let printed_range = format_script_range_with_config(src, text_range, filename, config)
         .map_err(|e| JsValue::from_str(&e.clone()))?;
 
+    let printed_range = offsets.encode_printed(printed_range).map_err(|e| JsValue::from_str(&e))?;
     serde_wasm_bindgen::to_value(&printed_range).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
+/// Formats the given JavaScript/TypeScript code, returning where the cursor ends up in the output.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatWithCursor, unchecked_return_type = "FormattedWithCursor")]
+pub fn format_with_cursor(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to format")] src: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "number | LineColumn",
+        param_description = "The cursor position, counted as given by `encoding`"
+    )]
+    cursor_offset: JsValue,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the cursor positions are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let cursor_offset: Position =
+        serde_wasm_bindgen::from_value(cursor_offset).map_err(|e| e.to_string())?;
+    let cursor_offset = offsets.to_utf8(cursor_offset)?;
+
+    let formatted = format_script_with_cursor(src, filename, cursor_offset, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_cursor(formatted)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given JavaScript/TypeScript code, returning the edits that turn it into the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatEdits, unchecked_return_type = "TextEdit[]")]
+pub fn format_edits(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to format")] src: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let edits = format_script_edits_with_config(src, filename, config)?;
+
+    serde_wasm_bindgen::to_value(&edits).map_err(|e| e.to_string())
+}
+
+/// Checks whether the given JavaScript/TypeScript code is already formatted, without returning the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(unchecked_return_type = "CheckResult")]
+pub fn check(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to check")] src: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let result = check_script_with_config(src, filename, config)?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
+}
+
+/// Formats several ranges of the given JavaScript/TypeScript code in one pass.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatRanges, unchecked_return_type = "FormattedRanges")]
+pub fn format_ranges(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to format")] src: &str,
+    #[wasm_bindgen(param_description = "The text or line ranges to format")] ranges: JsRanges,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the ranges and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let ranges: Vec<common::AnyRange> =
+        serde_wasm_bindgen::from_value(ranges.into()).map_err(|e| e.to_string())?;
+    let ranges = ranges
+        .iter()
+        .map(|range| range.to_text_range(&offsets))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let formatted = format_script_ranges_with_config(src, &ranges, filename, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_formatted(formatted)?).map_err(|e| e.to_string())
+}
+
 pub fn format_script_with_config(
     src: &str,
     filename: &str,
     config: BiomeConfig,
 ) -> Result<String, String> {
+    print_script(src, filename, config).map(|(printed, _)| printed.into_code())
+}
+
+/// Formats the given code and maps `cursor_offset` to where the cursor ends up in the output.
+///
+/// The cursor is mapped through the source markers of the printer: it keeps its distance to
+/// the start of the token it is in. When imports were sorted, the cursor is mapped by diffing.
+pub fn format_script_with_cursor(
+    src: &str,
+    filename: &str,
+    cursor_offset: u32,
+    config: BiomeConfig,
+) -> Result<FormattedWithCursor, String> {
+    FormattedWithCursor::check_cursor(src, cursor_offset)?;
+
+    let (printed, sorted) = print_script(src, filename, config)?;
+
+    if sorted {
+        return Ok(FormattedWithCursor::from_diff(src, printed.into_code(), cursor_offset));
+    }
+
+    let cursor_offset = map_cursor(&printed, cursor_offset);
+    Ok(FormattedWithCursor { code: printed.into_code(), cursor_offset })
+}
+
+/// Formats the whole code, returning whether imports were sorted before formatting.
+fn print_script(
+    src: &str,
+    filename: &str,
+    config: BiomeConfig,
+) -> Result<(biome_formatter::Printed, bool), String> {
     let source_type = source_type_from_filename(filename);
 
-    let tree =
+    let mut tree =
         parse(src, source_type, JsParserOptions::default().with_parse_class_parameter_decorators());
+    let mut sorted_imports = false;
+
+    // Imports are only sorted in valid scripts, the parser may have misread a broken one.
+    if !tree.has_errors()
+        && let Some(sort_options) = config.sort_imports()
+        && let Some(sorted) = sort_imports::sort_imports(src, &tree.tree(), sort_options)
+    {
+        tree = parse(
+            &sorted,
+            source_type,
+            JsParserOptions::default().with_parse_class_parameter_decorators(),
+        );
+        sorted_imports = true;
+    }
 
     let option = config.with_source_type(source_type).try_into()?;
 
-    biome_format_node(option, &tree.syntax(), false)
+    let printed = biome_format_node(option, &tree.syntax(), false)
         .map_err(|e| e.to_string())?
         .print()
-        .map(biome_formatter::Printed::into_code)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok((printed, sorted_imports))
+}
+
+/// Maps a source offset to the output through the source markers of `printed`.
+fn map_cursor(printed: &biome_formatter::Printed, offset: u32) -> u32 {
+    let markers: Vec<_> = printed
+        .sourcemap()
+        .iter()
+        .map(|marker| (u32::from(marker.source) as usize, u32::from(marker.dest) as usize))
+        .collect();
+
+    common::map_offset_with_markers(printed.as_code(), &markers, offset as usize) as u32
 }
 
 pub fn format_script_range_with_config(
@@ -162,6 +356,8 @@ pub fn format_script_range_with_config(
     filename: &str,
     config: BiomeConfig,
 ) -> Result<PrintedRange, String> {
+    range.check(src)?;
+
     let source_type = source_type_from_filename(filename);
 
     let tree =
@@ -169,12 +365,56 @@ pub fn format_script_range_with_config(
 
     let option = config.with_source_type(source_type).try_into()?;
 
-    let biome_range: BiomeTextRange = range.into();
+    let printed = biome_format_range(option, &tree.syntax(), to_biome_range(range))
+        .map_err(|e| e.to_string())?;
+
+    Ok(printed_range(printed))
+}
+
+/// Formats the given code, returning the minimal edits that turn it into the formatted code.
+pub fn format_script_edits_with_config(
+    src: &str,
+    filename: &str,
+    config: BiomeConfig,
+) -> Result<Vec<TextEdit>, String> {
+    let code = format_script_with_config(src, filename, config)?;
+
+    Ok(common::text_edits(src, &code))
+}
+
+/// Checks whether the given code is already formatted, reporting where it first differs otherwise.
+pub fn check_script_with_config(
+    src: &str,
+    filename: &str,
+    config: BiomeConfig,
+) -> Result<CheckResult, String> {
+    let code = format_script_with_config(src, filename, config)?;
+
+    Ok(CheckResult::new(src, &code))
+}
+
+/// Formats several ranges in one pass, merging the ranges that overlap.
+///
+/// The formatted ranges and the ranges they replace all refer to the original `src`.
+pub fn format_script_ranges_with_config(
+    src: &str,
+    ranges: &[TextRange],
+    filename: &str,
+    config: BiomeConfig,
+) -> Result<FormattedRanges, String> {
+    let source_type = source_type_from_filename(filename);
+
+    let tree =
+        parse(src, source_type, JsParserOptions::default().with_parse_class_parameter_decorators());
+
+    let option: JsFormatOptions = config.with_source_type(source_type).try_into()?;
 
-    let printed =
-        biome_format_range(option, &tree.syntax(), biome_range).map_err(|e| e.to_string())?;
+    common::format_ranges(src, ranges, |range| {
+        let printed = biome_format_range(option.clone(), &tree.syntax(), to_biome_range(range))
+            .map_err(|e| e.to_string())?;
 
-    Ok(printed.into())
+        Ok(printed_range(printed))
+    })
 }
 
 pub(crate) fn source_type_from_filename(mut filename: &str) -> JsFileSource {
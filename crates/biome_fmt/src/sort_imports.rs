@@ -0,0 +1,389 @@
+use std::cmp::Ordering;
+
+use biome_js_syntax::{AnyJsImportClause, AnyJsModuleItem, AnyJsRoot, JsImport};
+use biome_rowan::AstNode;
+use common::{SortImportsOptions, SortOrder};
+
+struct ImportEntry<'a> {
+    /// The import statement including attached comments, except for the first import of a run.
+    text: &'a str,
+    source: String,
+    group: usize,
+}
+
+/// Sorts runs of consecutive top-level imports in `src`.
+///
+/// Returns `None` when the source is already sorted or has no imports to sort.
+pub(crate) fn sort_imports(
+    src: &str,
+    root: &AnyJsRoot,
+    options: &SortImportsOptions,
+) -> Option<String> {
+    let AnyJsRoot::JsModule(module) = root else {
+        return None;
+    };
+
+    let mut runs: Vec<Vec<JsImport>> = vec![];
+    let mut current: Vec<JsImport> = vec![];
+
+    for item in module.items() {
+        let AnyJsModuleItem::JsImport(import) = item else {
+            runs.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+            continue;
+        };
+
+        let leading = leading_trivia(src, &import);
+        let is_barrier = (options.partition_by_newline && leading.matches('\n').count() > 1)
+            || (options.partition_by_comment && (leading.contains("//") || leading.contains("/*")))
+            || (!options.sort_side_effects && is_side_effect(&import));
+
+        if is_barrier && !current.is_empty() {
+            runs.push(std::mem::take(&mut current));
+        }
+
+        if !options.sort_side_effects && is_side_effect(&import) {
+            continue;
+        }
+
+        current.push(import);
+    }
+    runs.extend((!current.is_empty()).then_some(current));
+
+    let mut output = String::with_capacity(src.len());
+    let mut last = 0;
+
+    for run in runs {
+        // The comments before the first import of a run, e.g. a file header or the comment
+        // partitioning the runs, stay in place.
+        let start = usize::from(run[0].syntax().text_trimmed_range().start());
+        let end = chunk_end(src, &run[run.len() - 1]);
+
+        let entries: Vec<_> = run
+            .iter()
+            .enumerate()
+            .map(|(i, import)| {
+                let start = if i == 0 { start } else { chunk_start(src, import) };
+                import_entry(src, import, start, options)
+            })
+            .collect();
+
+        output.push_str(&src[last..start]);
+        output.push_str(&print_run(entries, options));
+        last = end;
+    }
+
+    output.push_str(&src[last..]);
+
+    (output != src).then_some(output)
+}
+
+fn print_run(mut entries: Vec<ImportEntry>, options: &SortImportsOptions) -> String {
+    entries.sort_by(|a, b| {
+        a.group.cmp(&b.group).then_with(|| {
+            let ordering = compare_source(&a.source, &b.source, options.ignore_case);
+            if options.order == SortOrder::Desc { ordering.reverse() } else { ordering }
+        })
+    });
+
+    let mut output = String::new();
+    let mut previous_group: Option<usize> = None;
+
+    for entry in entries {
+        if let Some(previous) = previous_group {
+            output.push('\n');
+            if previous != entry.group && newline_between(previous, entry.group, options) {
+                output.push('\n');
+            }
+        }
+        output.push_str(entry.text);
+        previous_group = Some(entry.group);
+    }
+
+    output
+}
+
+fn newline_between(previous: usize, next: usize, options: &SortImportsOptions) -> bool {
+    // The override only applies to adjacent groups, boundaries further apart fall back to the global setting.
+    if next == previous + 1
+        && let Some(Some(value)) = options.groups.newline_boundary_overrides.get(previous)
+    {
+        return *value;
+    }
+
+    options.newlines_between
+}
+
+fn compare_source(a: &str, b: &str, ignore_case: bool) -> Ordering {
+    if ignore_case {
+        a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b))
+    } else {
+        a.cmp(b)
+    }
+}
+
+fn import_entry<'a>(
+    src: &'a str,
+    import: &JsImport,
+    start: usize,
+    options: &SortImportsOptions,
+) -> ImportEntry<'a> {
+    let text = &src[start..chunk_end(src, import)];
+    let clause = import.import_clause().ok();
+    let source = clause
+        .as_ref()
+        .and_then(|clause| clause.source().ok())
+        .and_then(|source| source.as_js_module_source().cloned())
+        .and_then(|source| source.inner_string_text().ok())
+        .map(|text| text.to_string())
+        .unwrap_or_default();
+
+    let modifiers = import_modifiers(clause.as_ref());
+    let selector = classify_source(&source, options);
+    let group = group_index(&source, selector, &modifiers, options);
+
+    ImportEntry { text, source, group }
+}
+
+fn is_side_effect(import: &JsImport) -> bool {
+    matches!(import.import_clause(), Ok(AnyJsImportClause::JsImportBareClause(_)))
+}
+
+fn import_modifiers(clause: Option<&AnyJsImportClause>) -> Vec<&'static str> {
+    let mut modifiers = vec![];
+
+    let Some(clause) = clause else {
+        return modifiers;
+    };
+
+    if clause.type_token().is_some() {
+        modifiers.push("type");
+    } else {
+        modifiers.push("value");
+    }
+
+    match clause {
+        AnyJsImportClause::JsImportBareClause(_) => modifiers.push("side-effect"),
+        AnyJsImportClause::JsImportDefaultClause(_) => modifiers.push("default"),
+        AnyJsImportClause::JsImportNamedClause(_) => modifiers.push("named"),
+        AnyJsImportClause::JsImportNamespaceClause(_) => modifiers.push("namespace"),
+        AnyJsImportClause::JsImportCombinedClause(_) => {
+            modifiers.push("default");
+            modifiers.push("named");
+        }
+    }
+
+    modifiers
+}
+
+/// Classifies the import source into one of the predefined selectors.
+fn classify_source(source: &str, options: &SortImportsOptions) -> &'static str {
+    if source.starts_with("node:")
+        || NODE_BUILTINS.contains(&source.split('/').next().unwrap_or(""))
+    {
+        "builtin"
+    } else if options.internal_pattern.iter().any(|pattern| source.starts_with(pattern.as_str())) {
+        "internal"
+    } else if source.starts_with("..") {
+        "parent"
+    } else if matches!(source, "." | "./" | "./index") || source.starts_with("./index.") {
+        "index"
+    } else if source.starts_with("./") {
+        "sibling"
+    } else if source.starts_with('#') {
+        "subpath"
+    } else if source.is_empty() {
+        "unknown"
+    } else {
+        "external"
+    }
+}
+
+fn group_index(
+    source: &str,
+    selector: &str,
+    modifiers: &[&str],
+    options: &SortImportsOptions,
+) -> usize {
+    let groups = &options.groups.groups;
+
+    let custom = options.custom_groups.iter().find(|custom| {
+        custom.element_name_pattern.iter().any(|pattern| glob_match(pattern, source))
+            && custom.selector.as_deref().is_none_or(|s| modifiers.contains(&s))
+            && custom.modifiers.iter().all(|m| modifiers.contains(&m.as_str()))
+    });
+
+    if let Some(custom) = custom
+        && let Some(index) = groups.iter().position(|group| group.contains(&custom.group_name))
+    {
+        return index;
+    }
+
+    let position = groups.iter().position(|group| {
+        group.iter().any(|entry| {
+            let (modifier, name) = match entry.split_once('-') {
+                Some((modifier @ ("type" | "value"), name)) => (Some(modifier), name),
+                _ => (None, entry.as_str()),
+            };
+
+            modifier.is_none_or(|m| modifiers.contains(&m))
+                && (name == selector || (name == "import" && modifier.is_some()))
+        })
+    });
+
+    position
+        .or_else(|| groups.iter().position(|group| group.iter().any(|entry| entry == "unknown")))
+        .unwrap_or(groups.len())
+}
+
+/// Minimal glob matcher supporting `*` wildcards.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+
+    let Some(text) = text.strip_prefix(prefix) else {
+        return false;
+    };
+
+    (0..=text.len()).filter(|i| text.is_char_boundary(*i)).any(|i| glob_match(rest, &text[i..]))
+}
+
+fn leading_trivia<'a>(src: &'a str, import: &JsImport) -> &'a str {
+    let full = import.syntax().text_range();
+    let trimmed = import.syntax().text_trimmed_range();
+    &src[usize::from(full.start())..usize::from(trimmed.start())]
+}
+
+/// Start of the import including the comments attached before it.
+fn chunk_start(src: &str, import: &JsImport) -> usize {
+    let start = usize::from(import.syntax().text_range().start());
+    let leading = leading_trivia(src, import);
+    start + (leading.len() - leading.trim_start().len())
+}
+
+/// End of the import including a trailing comment on the same line.
+fn chunk_end(src: &str, import: &JsImport) -> usize {
+    let end = usize::from(import.syntax().text_range().end());
+    src[..end].trim_end().len()
+}
+
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::{BiomeConfig, format_script_with_config};
+
+    fn sort(src: &str, options: &str) -> String {
+        let config: BiomeConfig =
+            serde_json::from_str(&format!(r#"{{"sortImports":{options}}}"#)).unwrap();
+        format_script_with_config(src, "index.ts", config).unwrap()
+    }
+
+    #[test]
+    fn sorts_imports_into_default_groups() {
+        let src = r#"import b from "./b";
+import type { T } from "pkg";
+import fs from "node:fs";
+import a from "pkg";
+"#;
+
+        assert_eq!(
+            sort(src, "{}"),
+            r#"import type { T } from "pkg";
+
+import fs from "node:fs";
+import a from "pkg";
+
+import b from "./b";
+"#
+        );
+    }
+
+    #[test]
+    fn side_effect_imports_stay_in_place() {
+        let src = r#"import b from "b";
+import "polyfill";
+import a from "a";
+"#;
+
+        assert_eq!(sort(src, "{}"), src);
+    }
+
+    #[test]
+    fn file_header_stays_in_place() {
+        let src = r#"// @ts-check
+"use client";
+
+// Copyright header
+import b from "./b";
+// about a
+import a from "a";
+"#;
+
+        assert_eq!(
+            sort(src, "{}"),
+            r#"// @ts-check
+"use client";
+
+// Copyright header
+// about a
+import a from "a";
+
+import b from "./b";
+"#
+        );
+    }
+
+    #[test]
+    fn broken_scripts_are_not_sorted() {
+        let src = "import b from \"./b\";\nimport a from \"a\";\nlet = ;\n";
+        let config: BiomeConfig = serde_json::from_str(r#"{"sortImports":{}}"#).unwrap();
+
+        // Biome formats broken scripts, keeping the bogus parts verbatim.
+        let code = format_script_with_config(src, "index.ts", config).unwrap();
+        assert!(code.find("./b").unwrap() < code.find("\"a\"").unwrap(), "{code}");
+    }
+}
@@ -0,0 +1,60 @@
+/** Options for sorting import statements. */
+export interface SortImportsOptions {
+	/** Partition imports by newlines. Default is `false`. */
+	partitionByNewline?: boolean;
+
+	/** Partition imports by comments. Default is `false`. */
+	partitionByComment?: boolean;
+
+	/** Sort side effects imports. Default is `false`. */
+	sortSideEffects?: boolean;
+
+	/** Sort order (asc or desc). Default is ascending ("asc"). */
+	order?: "asc" | "desc";
+
+	/** Ignore case when sorting. Default is `true`. */
+	ignoreCase?: boolean;
+
+	/** Whether to insert blank lines between different import groups. Default is `true`. */
+	newlinesBetween?: boolean;
+
+	/** Prefixes for internal imports. Defaults to `["~/", "@/"]`. */
+	internalPattern?: string[];
+
+	/**
+	 * Groups configuration for organizing imports.
+	 * Each array element represents a group, and multiple group names in the same array are treated as one.
+	 * Accepts `string`, `string[]`, or `{ newlinesBetween: boolean }` marker objects.
+	 * Marker objects override the global `newlinesBetween` setting for the boundary between the adjacent groups.
+	 */
+	groups?: SortGroupItem[];
+
+	/** Define custom groups for matching specific imports. */
+	customGroups?: CustomGroupDefinition[];
+}
+
+/** A group item in the sort imports configuration.
+ * Can be a single group name, an array of group names, or a newlinesBetween marker.
+ */
+export type SortGroupItem = string | string[] | { newlinesBetween: boolean };
+
+/** Import selector for custom group matching. */
+export type ImportSelector = "value" | "type" | "default" | "namespace" | "side-effect";
+
+/** Import modifier for custom group matching. */
+export type ImportModifier = "type" | "value" | "default" | "namespace" | "named" | "side-effect";
+
+/** Custom group definition for sort imports. */
+export interface CustomGroupDefinition {
+	/** The name of the custom group. */
+	groupName: string;
+
+	/** Patterns to match import source names against. */
+	elementNamePattern: string[];
+
+	/** Selector to filter imports by type. */
+	selector?: ImportSelector;
+
+	/** Modifiers to further filter imports. */
+	modifiers?: ImportModifier[];
+}
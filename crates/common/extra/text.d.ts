@@ -0,0 +1,65 @@
+/**
+ * How offsets are counted: UTF-8 bytes (the default), UTF-16 code units as in JavaScript strings,
+ * or 0-based lines and UTF-16 columns as in the Language Server Protocol.
+ */
+export type OffsetEncoding = "utf8" | "utf16" | "line-column";
+
+/**
+ * A range in text, using offsets in the chosen `OffsetEncoding`, UTF-8 bytes by default.
+ */
+export interface TextRange {
+	start: number;
+	end: number;
+}
+
+/**
+ * A position as a 0-based line and UTF-16 column.
+ */
+export interface LineColumn {
+	line: number;
+	column: number;
+}
+
+/**
+ * A range in text, using lines and columns.
+ */
+export interface LineColumnRange {
+	start: LineColumn;
+	end: LineColumn;
+}
+
+/**
+ * Result of formatting a range of code.
+ */
+export interface PrintedRange {
+	code: string;
+	/** The replaced range, absent with an empty `code` when the range touches nothing to format. */
+	range?: TextRange | LineColumnRange;
+}
+
+/**
+ * Result of formatting with a cursor: the formatted code and the cursor offset within it.
+ */
+export interface FormattedWithCursor {
+	code: string;
+	cursorOffset: number | LineColumn;
+}
+
+/**
+ * An edit replacing a range of the source by new text.
+ * The source range is given in both UTF-8 byte offsets and UTF-16 code unit offsets.
+ */
+export interface TextEdit {
+	range: TextRange;
+	utf16Range: TextRange;
+	newText: string;
+}
+
+/**
+ * Result of checking whether code is already formatted.
+ * `first_difference` is the 1-based line and column where the code first differs from its formatted version.
+ */
+export interface CheckResult {
+	formatted: boolean;
+	first_difference?: { line: number; column: number };
+}
@@ -0,0 +1,185 @@
+//! The types of the WIT world in `wit/`, shared by the WebAssembly components of the formatters.
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::{
+    CheckResult, IndentStyle, LayoutConfig, LineEnding, Location, PrintedRange, TextRange,
+};
+
+mod bindings {
+    wit_bindgen::generate!({
+        world: "types-only",
+        path: "../../wit",
+    });
+}
+
+pub use bindings::wasm_fmt::formatter::types;
+
+impl types::Config {
+    /// The config as a JSON object with the keys of the JavaScript config of the language.
+    pub fn to_json(&self) -> Result<Value, String> {
+        let mut options = match &self.options {
+            Some(options) => match serde_json::from_str(options) {
+                Ok(Value::Object(options)) => options,
+                Ok(_) => return Err("the options must be a JSON object".into()),
+                Err(e) => return Err(format!("invalid options: {e}")),
+            },
+            None => Map::new(),
+        };
+        self.layout.write_json(&mut options);
+
+        Ok(Value::Object(options))
+    }
+}
+
+impl types::LayoutConfig {
+    /// Sets the layout options that are set in a JSON config, in place of the same options
+    /// under their JavaScript name.
+    pub fn write_json(&self, options: &mut Map<String, Value>) {
+        let layout = [
+            (
+                "indent_style",
+                "indentStyle",
+                self.indent_style.map(|x| IndentStyle::from(x).as_str().into()),
+            ),
+            ("indent_width", "indentWidth", self.indent_width.map(Value::from)),
+            ("line_width", "lineWidth", self.line_width.map(Value::from)),
+            (
+                "line_ending",
+                "lineEnding",
+                self.line_ending.map(|x| LineEnding::from(x).as_str().into()),
+            ),
+        ];
+
+        for (key, alias, value) in layout {
+            if let Some(value) = value {
+                options.remove(alias);
+                options.insert(key.into(), value);
+            }
+        }
+    }
+}
+
+/// Parses the config of a language, or returns its default without one.
+pub fn parse_config<T: DeserializeOwned + Default>(
+    config: Option<&types::Config>,
+) -> Result<T, String> {
+    let Some(config) = config else {
+        return Ok(T::default());
+    };
+
+    serde_json::from_value(config.to_json()?).map_err(|e| e.to_string())
+}
+
+impl From<types::IndentStyle> for IndentStyle {
+    fn from(indent_style: types::IndentStyle) -> Self {
+        match indent_style {
+            types::IndentStyle::Tab => IndentStyle::Tab,
+            types::IndentStyle::Space => IndentStyle::Space,
+        }
+    }
+}
+
+impl From<types::LineEnding> for LineEnding {
+    fn from(line_ending: types::LineEnding) -> Self {
+        match line_ending {
+            types::LineEnding::Lf => LineEnding::Lf,
+            types::LineEnding::Crlf => LineEnding::Crlf,
+        }
+    }
+}
+
+impl From<types::LayoutConfig> for LayoutConfig {
+    fn from(layout: types::LayoutConfig) -> Self {
+        Self {
+            indent_style: layout.indent_style.map(Into::into),
+            indent_width: layout.indent_width,
+            line_width: layout.line_width,
+            line_ending: layout.line_ending.map(Into::into),
+        }
+    }
+}
+
+impl From<types::TextRange> for TextRange {
+    fn from(range: types::TextRange) -> Self {
+        Self { start: range.start, end: range.end }
+    }
+}
+
+impl From<TextRange> for types::TextRange {
+    fn from(range: TextRange) -> Self {
+        Self { start: range.start, end: range.end }
+    }
+}
+
+impl From<PrintedRange> for types::PrintedRange {
+    fn from(printed: PrintedRange) -> Self {
+        Self { code: printed.code, range: printed.range.map(Into::into) }
+    }
+}
+
+impl From<Location> for types::Location {
+    fn from(location: Location) -> Self {
+        Self { line: location.line, column: location.column }
+    }
+}
+
+impl From<CheckResult> for types::CheckResult {
+    fn from(result: CheckResult) -> Self {
+        Self {
+            formatted: result.formatted,
+            first_difference: result.first_difference.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_takes_precedence_over_options() {
+        let config = types::Config {
+            layout: types::LayoutConfig {
+                indent_style: Some(types::IndentStyle::Tab),
+                indent_width: None,
+                line_width: Some(100),
+                line_ending: None,
+            },
+            options: Some(
+                r#"{"indentStyle": "space", "indentWidth": 4, "quoteStyle": "single"}"#.into(),
+            ),
+        };
+
+        let json = config.to_json().unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "indent_style": "tab",
+                "indentWidth": 4,
+                "line_width": 100,
+                "quoteStyle": "single",
+            })
+        );
+
+        let layout: LayoutConfig = parse_config(Some(&config)).unwrap();
+        assert!(layout.indent_style().is_some_and(|x| x.use_tabs()));
+        assert_eq!(layout.indent_width(), Some(4));
+    }
+
+    #[test]
+    fn rejects_options_other_than_objects() {
+        let config = types::Config {
+            layout: types::LayoutConfig {
+                indent_style: None,
+                indent_width: None,
+                line_width: None,
+                line_ending: None,
+            },
+            options: Some("[]".into()),
+        };
+
+        assert!(config.to_json().is_err());
+    }
+}
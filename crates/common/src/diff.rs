@@ -0,0 +1,653 @@
+//! Diffing the source against the formatted code, to map offsets and compute minimal edits.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+use crate::{TextEdit, TextRange};
+
+/// Diffs needing more edits than this are split at the items occurring once in both texts,
+/// and the pieces between are diffed on their own.
+const MAX_EDITS: usize = 1024;
+
+/// Maps a byte offset in `old` to the matching offset in `new`, a formatted version of `old`.
+///
+/// Tokens are aligned ignoring whitespace and quote style. A cursor inside or right after
+/// a token stays with it; a cursor in whitespace moves to the start of the next token.
+pub fn map_offset(old: &str, new: &str, offset: usize) -> usize {
+    let offset = offset.min(old.len());
+    let (a, b) = (tokens(old), tokens(new));
+
+    let mut matches = vec![None; a.len()];
+    for (a_start, b_start, len) in matching_runs(&a, &b) {
+        for i in 0..len {
+            matches[a_start + i] = Some(b_start + i);
+        }
+    }
+
+    let next = a.partition_point(|token| token.end() <= offset);
+
+    if let Some(j) = matches.get(next).copied().flatten()
+        && a[next].start <= offset
+    {
+        return b[j].start + (offset - a[next].start).min(b[j].text.len());
+    }
+    if next > 0
+        && a[next - 1].end() == offset
+        && let Some(j) = matches[next - 1]
+    {
+        return b[j].end();
+    }
+    if a.get(next).is_some_and(|token| offset < token.start)
+        && let Some(j) = matches[next]
+    {
+        return b[j].start;
+    }
+
+    matches[..next]
+        .iter()
+        .rev()
+        .find_map(|&j| j.map(|j| b[j].end()))
+        .or_else(|| matches[next..].iter().find_map(|&j| j.map(|j| b[j].start)))
+        .unwrap_or(0)
+}
+
+/// Maps a byte offset of the source to the output `code` through the source markers of a printer,
+/// pairs of a source offset and the offset in `code` where the text at it was printed.
+///
+/// The offset keeps its distance to the marker before it, up to the next printed marker.
+pub fn map_offset_with_markers(code: &str, markers: &[(usize, usize)], offset: usize) -> usize {
+    let Some(&(source, dest)) =
+        markers.iter().filter(|&&(source, _)| source <= offset).max_by_key(|&&(source, _)| source)
+    else {
+        return 0;
+    };
+
+    let next_dest = markers
+        .iter()
+        .map(|&(_, next)| next)
+        .filter(|&next| next > dest)
+        .min()
+        .unwrap_or(code.len());
+
+    let mut mapped = (dest + offset - source).min(next_dest);
+    while !code.is_char_boundary(mapped) {
+        mapped -= 1;
+    }
+    mapped
+}
+
+/// Computes the edits turning `old` into `new`.
+///
+/// Changed lines are found first, then the words, whitespace and punctuation that changed within them.
+pub fn text_edits(old: &str, new: &str) -> Vec<TextEdit> {
+    let (old_lines, new_lines) = (split_offsets(old, lines), split_offsets(new, lines));
+    let mut edits = vec![];
+
+    for (old_hunk, new_hunk) in gaps(&old_lines, &new_lines, old, new) {
+        let (old_text, new_text) = (&old[old_hunk.clone()], &new[new_hunk.clone()]);
+        let (old_words, new_words) =
+            (split_offsets(old_text, words), split_offsets(new_text, words));
+
+        for (old_range, new_range) in gaps(&old_words, &new_words, old_text, new_text) {
+            edits.push(TextEdit {
+                range: TextRange {
+                    start: (old_hunk.start + old_range.start) as u32,
+                    end: (old_hunk.start + old_range.end) as u32,
+                },
+                utf16_range: TextRange::default(),
+                new_text: new_text[new_range].to_string(),
+            });
+        }
+    }
+
+    let mut utf16 = Utf16Offsets::new(old);
+    for edit in &mut edits {
+        edit.utf16_range = TextRange {
+            start: utf16.offset(edit.range.start as usize) as u32,
+            end: utf16.offset(edit.range.end as usize) as u32,
+        };
+    }
+
+    edits
+}
+
+/// Options of [`unified_diff`].
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiffOptions {
+    /// Number of unchanged lines shown around changes, 3 by default.
+    pub context: Option<u32>,
+    /// Whether to color the diff with ANSI escape codes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color: bool,
+}
+
+/// Renders the changes from `old` to `new` as a unified diff, with `filename` in the file headers.
+///
+/// Returns an empty string when the texts are equal.
+pub fn unified_diff(old: &str, new: &str, filename: &str, options: &DiffOptions) -> String {
+    let (old_lines, new_lines) = (lines(old), lines(new));
+    let context = options.context.unwrap_or(3) as usize;
+
+    let mut changes = vec![];
+    let (mut x, mut y) = (0, 0);
+    let end = (old_lines.len(), new_lines.len(), 0);
+    for (run_x, run_y, len) in matching_runs(&old_lines, &new_lines).into_iter().chain([end]) {
+        if run_x > x || run_y > y {
+            changes.push(((x, run_x), (y, run_y)));
+        }
+        (x, y) = (run_x + len, run_y + len);
+    }
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let style = |code: &str, text: &str| {
+        if options.color { format!("\x1b[{code}m{text}\x1b[0m\n") } else { format!("{text}\n") }
+    };
+    let print_lines = |out: &mut String, prefix: char, code: &str, lines: &[&str]| {
+        for line in lines {
+            out.push_str(&style(code, &format!("{prefix}{}", line.trim_end_matches(['\n', '\r']))));
+            if !line.ends_with('\n') {
+                out.push_str("\\ No newline at end of file\n");
+            }
+        }
+    };
+
+    let mut out = style("1", &format!("--- a/{filename}"));
+    out.push_str(&style("1", &format!("+++ b/{filename}")));
+
+    let mut first = 0;
+    while first < changes.len() {
+        // Changes closer than twice the context share a hunk.
+        let mut last = first;
+        while last + 1 < changes.len() && changes[last + 1].0.0 - changes[last].0.1 <= 2 * context {
+            last += 1;
+        }
+
+        let ((old_start, _), (new_start, _)) = changes[first];
+        let ((_, old_end), (_, new_end)) = changes[last];
+        let before = old_start.min(context);
+        let after = (old_lines.len() - old_end).min(context);
+        let (hunk_old_start, hunk_new_start) = (old_start - before, new_start - before);
+        let (hunk_old_end, hunk_new_end) = (old_end + after, new_end + after);
+
+        out.push_str(&style(
+            "36",
+            &format!(
+                "@@ -{} +{} @@",
+                hunk_range(hunk_old_start, hunk_old_end),
+                hunk_range(hunk_new_start, hunk_new_end)
+            ),
+        ));
+
+        let mut line = hunk_old_start;
+        for &((old_from, old_to), (new_from, new_to)) in &changes[first..=last] {
+            print_lines(&mut out, ' ', "0", &old_lines[line..old_from]);
+            print_lines(&mut out, '-', "31", &old_lines[old_from..old_to]);
+            print_lines(&mut out, '+', "32", &new_lines[new_from..new_to]);
+            line = old_to;
+        }
+        print_lines(&mut out, ' ', "0", &old_lines[line..hunk_old_end]);
+
+        first = last + 1;
+    }
+
+    out
+}
+
+/// Formats the `start,count` of a hunk header, with 1-based lines.
+fn hunk_range(start: usize, end: usize) -> String {
+    match end - start {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        count => format!("{},{count}", start + 1),
+    }
+}
+
+/// Splits `src` with `split` into contiguous pieces, paired with their byte offsets.
+fn split_offsets<'a>(src: &'a str, split: fn(&'a str) -> Vec<&'a str>) -> Vec<(usize, &'a str)> {
+    let mut offset = 0;
+    split(src)
+        .into_iter()
+        .map(|piece| {
+            offset += piece.len();
+            (offset - piece.len(), piece)
+        })
+        .collect()
+}
+
+fn lines(src: &str) -> Vec<&str> {
+    src.split_inclusive('\n').collect()
+}
+
+/// Splits `src` into words, whitespace runs and single punctuation characters.
+fn words(src: &str) -> Vec<&str> {
+    let kind = |c: char| {
+        if c.is_whitespace() {
+            0
+        } else if c.is_alphanumeric() || matches!(c, '_' | '$') {
+            1
+        } else {
+            2
+        }
+    };
+    let mut words = vec![];
+    let mut start = 0;
+    let mut previous = None;
+
+    for (i, c) in src.char_indices() {
+        let current = kind(c);
+        if i > start && (current == 2 || previous != Some(current)) {
+            words.push(&src[start..i]);
+            start = i;
+        }
+        previous = Some(current);
+    }
+    if start < src.len() {
+        words.push(&src[start..]);
+    }
+
+    words
+}
+
+/// Finds the byte ranges of `old` and `new` between the matching runs of their pieces.
+fn gaps(
+    old_pieces: &[(usize, &str)],
+    new_pieces: &[(usize, &str)],
+    old: &str,
+    new: &str,
+) -> Vec<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    let old_texts: Vec<&str> = old_pieces.iter().map(|&(_, text)| text).collect();
+    let new_texts: Vec<&str> = new_pieces.iter().map(|&(_, text)| text).collect();
+    let offset = |pieces: &[(usize, &str)], index: usize, len: usize| {
+        pieces.get(index).map_or(len, |&(offset, _)| offset)
+    };
+
+    let mut gaps = vec![];
+    let (mut x, mut y) = (0, 0);
+    let runs = matching_runs(&old_texts, &new_texts);
+    let end = (old_texts.len(), new_texts.len(), 0);
+
+    for (run_x, run_y, len) in runs.into_iter().chain([end]) {
+        if run_x > x || run_y > y {
+            gaps.push((
+                offset(old_pieces, x, old.len())..offset(old_pieces, run_x, old.len()),
+                offset(new_pieces, y, new.len())..offset(new_pieces, run_y, new.len()),
+            ));
+        }
+        (x, y) = (run_x + len, run_y + len);
+    }
+
+    gaps
+}
+
+/// Converts increasing UTF-8 byte offsets to UTF-16 offsets.
+struct Utf16Offsets<'a> {
+    src: &'a str,
+    byte: usize,
+    utf16: usize,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, byte: 0, utf16: 0 }
+    }
+
+    fn offset(&mut self, byte: usize) -> usize {
+        self.utf16 += self.src[self.byte..byte].encode_utf16().count();
+        self.byte = byte;
+        self.utf16
+    }
+}
+
+/// A token of source text: a word, or a single punctuation character.
+#[derive(Debug)]
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl Token<'_> {
+    fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// The text compared across the source and the formatted code, the same for all quotes.
+    fn normalized(&self) -> &str {
+        if matches!(self.text, "'" | "\"" | "`") { "\"" } else { self.text }
+    }
+}
+
+impl PartialEq for Token<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl Eq for Token<'_> {}
+
+impl Hash for Token<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized().hash(state);
+    }
+}
+
+fn tokens(src: &str) -> Vec<Token<'_>> {
+    let is_word = |c: char| c.is_alphanumeric() || matches!(c, '_' | '$');
+    let mut tokens = vec![];
+    let mut chars = src.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        if is_word(c) {
+            while let Some(&(i, c)) = chars.peek().filter(|&&(_, c)| is_word(c)) {
+                end = i + c.len_utf8();
+                chars.next();
+            }
+        }
+        tokens.push(Token { text: &src[start..end], start });
+    }
+
+    tokens
+}
+
+/// Finds the runs of equal items in a shortest edit script from `a` to `b`,
+/// as `(a_start, b_start, len)` in order.
+///
+/// When the sequences need more than [`MAX_EDITS`] edits, the script is only shortest
+/// between the items occurring once in each of them.
+fn matching_runs<T: Eq + Hash>(a: &[T], b: &[T]) -> Vec<(usize, usize, usize)> {
+    let mut runs: Vec<(usize, usize, usize)> = vec![];
+    push_matching_runs(a, b, (0, 0), &mut runs);
+
+    let mut merged: Vec<(usize, usize, usize)> = Vec::with_capacity(runs.len());
+    for run in runs {
+        match merged.last_mut() {
+            Some(last) if last.0 + last.2 == run.0 && last.1 + last.2 == run.1 => last.2 += run.2,
+            _ => merged.push(run),
+        }
+    }
+
+    merged
+}
+
+/// Pushes the matching runs of `a` and `b`, which start at `offsets` in the whole sequences.
+fn push_matching_runs<T: Eq + Hash>(
+    a: &[T],
+    b: &[T],
+    (x, y): (usize, usize),
+    runs: &mut Vec<(usize, usize, usize)>,
+) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix =
+        a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+
+    if prefix > 0 {
+        runs.push((x, y, prefix));
+    }
+
+    let (a_middle, b_middle) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let (x_middle, y_middle) = (x + prefix, y + prefix);
+
+    if let Some(middle) = myers(a_middle, b_middle) {
+        runs.extend(middle.into_iter().map(|(i, j, len)| (x_middle + i, y_middle + j, len)));
+    } else {
+        let anchors = unique_anchors(a_middle, b_middle);
+        let (mut i, mut j) = (0, 0);
+        for &(anchor_i, anchor_j) in &anchors {
+            let pieces = (&a_middle[i..anchor_i], &b_middle[j..anchor_j]);
+            push_matching_runs(pieces.0, pieces.1, (x_middle + i, y_middle + j), runs);
+            runs.push((x_middle + anchor_i, y_middle + anchor_j, 1));
+            (i, j) = (anchor_i + 1, anchor_j + 1);
+        }
+        // Without anchors, the middle is left unmatched.
+        if !anchors.is_empty() {
+            let pieces = (&a_middle[i..], &b_middle[j..]);
+            push_matching_runs(pieces.0, pieces.1, (x_middle + i, y_middle + j), runs);
+        }
+    }
+
+    if suffix > 0 {
+        runs.push((x + a.len() - suffix, y + b.len() - suffix, suffix));
+    }
+}
+
+/// Pairs the items occurring once in both `a` and `b`, keeping the longest run of pairs
+/// in the same order in both, as `(a_index, b_index)`.
+fn unique_anchors<T: Eq + Hash>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    // Occurrences in `a`, occurrences in `b` and the index in `a` of every item.
+    let mut counts: HashMap<&T, (usize, usize, usize)> = HashMap::new();
+    for (i, item) in a.iter().enumerate() {
+        let count = counts.entry(item).or_default();
+        count.0 += 1;
+        count.2 = i;
+    }
+    for item in b {
+        if let Some(count) = counts.get_mut(item) {
+            count.1 += 1;
+        }
+    }
+
+    let pairs: Vec<(usize, usize)> = b
+        .iter()
+        .enumerate()
+        .filter_map(|(j, item)| {
+            let &(in_a, in_b, i) = counts.get(item)?;
+            (in_a == 1 && in_b == 1).then_some((i, j))
+        })
+        .collect();
+
+    // The longest increasing subsequence of the `a` indices, by patience sorting.
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![None; pairs.len()];
+    for (k, &(i, _)) in pairs.iter().enumerate() {
+        let position = tails.partition_point(|&tail| pairs[tail].0 < i);
+        previous[k] = position.checked_sub(1).map(|p| tails[p]);
+        if position == tails.len() {
+            tails.push(k);
+        } else {
+            tails[position] = k;
+        }
+    }
+
+    let mut anchors = vec![];
+    let mut next = tails.last().copied();
+    while let Some(k) = next {
+        anchors.push(pairs[k]);
+        next = previous[k];
+    }
+    anchors.reverse();
+    anchors
+}
+
+/// Myers' O(ND) difference algorithm, keeping the frontier of every step to trace the path back.
+fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Option<Vec<(usize, usize, usize)>> {
+    let (n, m) = (a.len(), b.len());
+    let max = n + m;
+    let offset = max as isize + 1;
+    let mut v = vec![0usize; 2 * max + 3];
+    let mut trace: Vec<(usize, Vec<usize>)> = vec![];
+
+    for d in 0..=max.min(MAX_EDITS) as isize {
+        let low = (offset - d - 1) as usize;
+        trace.push((low, v[low..=(offset + d + 1) as usize].to_vec()));
+
+        for k in (-d..=d).step_by(2) {
+            let index = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            while x < n && y < m && a[x] == b[y] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, offset, n, m));
+            }
+        }
+    }
+
+    None
+}
+
+fn backtrack(
+    trace: &[(usize, Vec<usize>)],
+    offset: isize,
+    mut x: usize,
+    mut y: usize,
+) -> Vec<(usize, usize, usize)> {
+    let mut runs = vec![];
+
+    for (d, (low, v)) in trace.iter().enumerate().rev() {
+        let get = |k: isize| v[(offset + k) as usize - low];
+        let (d, k) = (d as isize, x as isize - y as isize);
+
+        let (start_x, start_y, prev) = if d == 0 {
+            (0, 0, (0, 0))
+        } else {
+            let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) { k + 1 } else { k - 1 };
+            let prev_x = get(prev_k);
+            let prev_y = (prev_x as isize - prev_k) as usize;
+            if prev_k == k + 1 {
+                (prev_x, prev_y + 1, (prev_x, prev_y))
+            } else {
+                (prev_x + 1, prev_y, (prev_x, prev_y))
+            }
+        };
+
+        if x > start_x {
+            runs.push((start_x, start_y, x - start_x));
+        }
+        (x, y) = prev;
+    }
+
+    runs.reverse();
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_matching_runs() {
+        let (a, b) = (b"abcabba".as_slice(), b"cbabac".as_slice());
+        let runs = matching_runs(a, b);
+
+        let matched: usize = runs.iter().map(|&(_, _, len)| len).sum();
+        assert_eq!(matched, 4);
+        for (x, y, len) in runs {
+            assert_eq!(a[x..x + len], b[y..y + len]);
+        }
+    }
+
+    #[test]
+    fn splits_long_diffs_at_unique_items() {
+        let old: Vec<String> = (0..3000).map(|i| format!("{i}\n")).collect();
+        let new: Vec<String> = (0..3000)
+            .map(|i| if i % 2 == 0 { format!("{i}\n") } else { format!("x{i}\n") })
+            .collect();
+
+        let runs = matching_runs(&old, &new);
+        let matched: usize = runs.iter().map(|&(_, _, len)| len).sum();
+        assert_eq!(matched, 1500);
+
+        let edits = text_edits(&old.concat(), &new.concat());
+        assert_eq!(edits.len(), 1500);
+        assert!(
+            edits.iter().all(|edit| edit.new_text.starts_with('x') && edit.new_text.len() <= 5)
+        );
+    }
+
+    #[test]
+    fn computes_minimal_edits() {
+        let old = "const a = {b:1};\n// ünïcode\nlet c='d'\n";
+        let new = "const a = { b: 1 };\n// ünïcode\nlet c = \"d\";\n";
+
+        let edits = text_edits(old, new);
+        let mut applied = old.to_string();
+        for edit in edits.iter().rev() {
+            applied
+                .replace_range(edit.range.start as usize..edit.range.end as usize, &edit.new_text);
+        }
+        assert_eq!(applied, new);
+        assert!(edits.iter().all(|edit| edit.new_text.len() <= 2));
+
+        let last = edits.last().unwrap();
+        assert_eq!(last.range.start - last.utf16_range.start, 2);
+    }
+
+    #[test]
+    fn counts_astral_chars_as_two_utf16_units() {
+        let old = "let s = '😀𝄞';\nf(a,b)\n";
+        let new = "let s = \"😀𝄞\";\nf(a, b);\n";
+
+        let edits = text_edits(old, new);
+        let mut applied = old.to_string();
+        for edit in edits.iter().rev() {
+            applied
+                .replace_range(edit.range.start as usize..edit.range.end as usize, &edit.new_text);
+        }
+        assert_eq!(applied, new);
+
+        let utf16: Vec<u16> = old.encode_utf16().collect();
+        // Each of the two astral chars is 4 bytes and 2 UTF-16 units.
+        let closing_quote = edits.iter().find(|edit| edit.range.start == 17).unwrap();
+        assert_eq!(closing_quote.utf16_range, TextRange { start: 13, end: 14 });
+        for edit in &edits {
+            let (start, end) = (edit.range.start as usize, edit.range.end as usize);
+            let (utf16_start, utf16_end) =
+                (edit.utf16_range.start as usize, edit.utf16_range.end as usize);
+            assert_eq!(
+                String::from_utf16(&utf16[utf16_start..utf16_end]).unwrap(),
+                old[start..end]
+            );
+        }
+    }
+
+    #[test]
+    fn renders_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\n";
+        let options = DiffOptions { context: Some(1), color: false };
+
+        assert_eq!(
+            unified_diff(old, new, "x.js", &options),
+            "--- a/x.js\n+++ b/x.js\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -8,2 +8,2 @@\n h\n-i\n\\ No newline at end of file\n+i\n"
+        );
+        assert_eq!(unified_diff(new, new, "x.js", &options), "");
+    }
+
+    #[test]
+    fn maps_offsets_through_markers() {
+        let code = "let a = [1, 2];\n";
+        let markers = [(0, 0), (4, 4), (5, 6), (6, 8), (7, 9), (8, 10), (9, 12), (10, 13)];
+
+        assert_eq!(map_offset_with_markers(code, &markers, 2), 2);
+        assert_eq!(map_offset_with_markers(code, &markers, 9), 12);
+        assert_eq!(map_offset_with_markers(code, &markers, 11), 14);
+    }
+
+    #[test]
+    fn maps_offsets_through_formatting() {
+        let old = "let a=['x',\n  b ]";
+        let new = "let a = [\"x\", b];\n";
+
+        assert_eq!(map_offset(old, new, old.find('x').unwrap()), new.find('x').unwrap());
+        assert_eq!(map_offset(old, new, old.find('b').unwrap() + 1), new.find('b').unwrap() + 1);
+        assert_eq!(map_offset(old, new, old.find("  b").unwrap()), new.find('b').unwrap());
+        assert_eq!(map_offset(old, new, old.len()), new.find(']').unwrap() + 1);
+    }
+}
@@ -0,0 +1,255 @@
+//! Conversions between UTF-8 byte offsets and the offsets used by editors.
+
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{FormattedRanges, FormattedWithCursor, PrintedRange, TextRange};
+
+/// How offsets are counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OffsetEncoding {
+    /// UTF-8 bytes, as in Rust strings.
+    #[default]
+    Utf8,
+    /// UTF-16 code units, as in JavaScript strings.
+    Utf16,
+    /// 0-based lines and UTF-16 columns, as in the Language Server Protocol.
+    LineColumn,
+}
+
+impl FromStr for OffsetEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "utf8" | "utf-8" => Ok(Self::Utf8),
+            "utf16" | "utf-16" => Ok(Self::Utf16),
+            "line-column" | "lineColumn" => Ok(Self::LineColumn),
+            _ => Err(format!("unknown offset encoding `{s}`")),
+        }
+    }
+}
+
+/// A position in text: an offset, or a 0-based line and UTF-16 column.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    Offset(u32),
+    LineColumn { line: u32, column: u32 },
+}
+
+/// A range in text, in any [`OffsetEncoding`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodedRange {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A [`PrintedRange`] with the range in the caller's [`OffsetEncoding`].
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Clone, Debug)]
+pub struct EncodedPrintedRange {
+    pub code: String,
+    pub range: Option<EncodedRange>,
+}
+
+/// [`FormattedRanges`] with the ranges in the caller's [`OffsetEncoding`].
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Clone, Debug)]
+pub struct EncodedFormattedRanges {
+    pub code: String,
+    pub ranges: Vec<EncodedPrintedRange>,
+}
+
+/// A [`FormattedWithCursor`] with the cursor in the caller's [`OffsetEncoding`].
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug)]
+pub struct EncodedFormattedWithCursor {
+    pub code: String,
+    pub cursor_offset: Position,
+}
+
+/// Converts positions in `src` between UTF-8 byte offsets and an [`OffsetEncoding`].
+pub struct OffsetConverter<'a> {
+    src: &'a str,
+    encoding: OffsetEncoding,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> OffsetConverter<'a> {
+    #[must_use]
+    pub fn new(src: &'a str, encoding: OffsetEncoding) -> Self {
+        let line_starts = std::iter::once(0).chain(src.match_indices('\n').map(|(i, _)| i + 1));
+        Self { src, encoding, line_starts: line_starts.collect() }
+    }
+
+    #[must_use]
+    pub fn src(&self) -> &'a str {
+        self.src
+    }
+
+    /// Converts `position` to a UTF-8 byte offset.
+    ///
+    /// Lines and columns are accepted whatever the encoding.
+    pub fn to_utf8(&self, position: Position) -> Result<u32, String> {
+        let offset = match (position, self.encoding) {
+            (Position::Offset(offset), OffsetEncoding::Utf8) => {
+                if !self.src.is_char_boundary(offset as usize) {
+                    return Err(format!("offset {offset} is out of bounds"));
+                }
+                offset as usize
+            }
+            (Position::Offset(offset), OffsetEncoding::Utf16) => {
+                utf16_to_utf8(self.src, offset as usize)
+                    .ok_or_else(|| format!("UTF-16 offset {offset} is out of bounds"))?
+            }
+            (Position::Offset(_), OffsetEncoding::LineColumn) => {
+                return Err("expected a line and column position".into());
+            }
+            (Position::LineColumn { line, column }, _) => {
+                let start = *self
+                    .line_starts
+                    .get(line as usize)
+                    .ok_or_else(|| format!("line {line} is out of bounds"))?;
+                let end = self.line_starts.get(line as usize + 1).map_or(self.src.len(), |&next| {
+                    next - 1 - usize::from(self.src[..next - 1].ends_with('\r'))
+                });
+                let line_text = &self.src[start..end];
+
+                let column = utf16_to_utf8(line_text, column as usize)
+                    .ok_or_else(|| format!("column {column} of line {line} is out of bounds"))?;
+                start + column
+            }
+        };
+
+        Ok(offset as u32)
+    }
+
+    /// Converts a UTF-8 byte offset to a position in the encoding.
+    pub fn from_utf8(&self, offset: u32) -> Result<Position, String> {
+        let offset = offset as usize;
+        if !self.src.is_char_boundary(offset) {
+            return Err(format!("offset {offset} is out of bounds"));
+        }
+
+        Ok(match self.encoding {
+            OffsetEncoding::Utf8 => Position::Offset(offset as u32),
+            OffsetEncoding::Utf16 => {
+                Position::Offset(self.src[..offset].encode_utf16().count() as u32)
+            }
+            OffsetEncoding::LineColumn => {
+                let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+                let column = self.src[self.line_starts[line]..offset].encode_utf16().count();
+                Position::LineColumn { line: line as u32, column: column as u32 }
+            }
+        })
+    }
+
+    pub fn range_to_utf8(&self, range: EncodedRange) -> Result<TextRange, String> {
+        let range = TextRange { start: self.to_utf8(range.start)?, end: self.to_utf8(range.end)? };
+        range.check(self.src)?;
+        Ok(range)
+    }
+
+    pub fn range_from_utf8(&self, range: TextRange) -> Result<EncodedRange, String> {
+        Ok(EncodedRange { start: self.from_utf8(range.start)?, end: self.from_utf8(range.end)? })
+    }
+
+    pub fn encode_printed(&self, printed: PrintedRange) -> Result<EncodedPrintedRange, String> {
+        Ok(EncodedPrintedRange {
+            code: printed.code,
+            range: printed.range.map(|range| self.range_from_utf8(range)).transpose()?,
+        })
+    }
+
+    /// Encodes the cursor of `formatted`, counted in the formatted code rather than in `src`.
+    pub fn encode_cursor(
+        &self,
+        formatted: FormattedWithCursor,
+    ) -> Result<EncodedFormattedWithCursor, String> {
+        let cursor_offset = OffsetConverter::new(&formatted.code, self.encoding)
+            .from_utf8(formatted.cursor_offset)?;
+        Ok(EncodedFormattedWithCursor { code: formatted.code, cursor_offset })
+    }
+
+    pub fn encode_formatted(
+        &self,
+        formatted: FormattedRanges,
+    ) -> Result<EncodedFormattedRanges, String> {
+        Ok(EncodedFormattedRanges {
+            code: formatted.code,
+            ranges: formatted
+                .ranges
+                .into_iter()
+                .map(|printed| self.encode_printed(printed))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Finds the UTF-8 offset of the UTF-16 offset `utf16`, or `None` past the end or inside a char.
+fn utf16_to_utf8(src: &str, utf16: usize) -> Option<usize> {
+    let mut units = 0;
+    for (i, c) in src.char_indices() {
+        if units >= utf16 {
+            return (units == utf16).then_some(i);
+        }
+        units += c.len_utf16();
+    }
+    (units == utf16).then_some(src.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_offsets() {
+        let src = "a😀b\r\nç = 1\n";
+        let b = src.find('b').unwrap() as u32;
+        let one = src.find('1').unwrap() as u32;
+
+        let utf16 = OffsetConverter::new(src, OffsetEncoding::Utf16);
+        assert_eq!(utf16.to_utf8(Position::Offset(3)), Ok(b));
+        assert!(utf16.to_utf8(Position::Offset(2)).is_err());
+        assert_eq!(utf16.from_utf8(b), Ok(Position::Offset(3)));
+
+        let lines = OffsetConverter::new(src, OffsetEncoding::LineColumn);
+        assert_eq!(lines.to_utf8(Position::LineColumn { line: 1, column: 4 }), Ok(one));
+        assert_eq!(lines.to_utf8(Position::LineColumn { line: 0, column: 4 }), Ok(b + 1));
+        assert_eq!(lines.from_utf8(one), Ok(Position::LineColumn { line: 1, column: 4 }));
+    }
+
+    #[test]
+    fn rejects_positions_inside_chars() {
+        let src = "a😀b\nc";
+
+        let lines = OffsetConverter::new(src, OffsetEncoding::LineColumn);
+        assert!(lines.to_utf8(Position::LineColumn { line: 0, column: 2 }).is_err());
+        assert!(lines.to_utf8(Position::LineColumn { line: 0, column: 5 }).is_err());
+        assert!(lines.to_utf8(Position::LineColumn { line: 1, column: 2 }).is_err());
+
+        for encoding in [OffsetEncoding::Utf8, OffsetEncoding::Utf16, OffsetEncoding::LineColumn] {
+            let offsets = OffsetConverter::new(src, encoding);
+            assert!(offsets.from_utf8(2).is_err());
+            assert!(offsets.from_utf8(src.len() as u32 + 1).is_err());
+        }
+    }
+
+    #[test]
+    fn encodes_cursors_in_the_formatted_code() {
+        let formatted = FormattedWithCursor { code: "😀\nb = 1;\n".into(), cursor_offset: 9 };
+
+        let lines = OffsetConverter::new("b=1", OffsetEncoding::LineColumn);
+        let encoded = lines.encode_cursor(formatted.clone()).unwrap();
+        assert_eq!(encoded.cursor_offset, Position::LineColumn { line: 1, column: 4 });
+
+        let utf16 = OffsetConverter::new("b=1", OffsetEncoding::Utf16);
+        assert_eq!(utf16.encode_cursor(formatted).unwrap().cursor_offset, Position::Offset(7));
+    }
+}
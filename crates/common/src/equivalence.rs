@@ -0,0 +1,135 @@
+//! Comparison of the syntax trees of code before and after formatting.
+
+use crate::{Location, SourceError};
+
+/// A significant token or node of a syntax tree, normalized so that formatting does not change it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeToken {
+    pub text: String,
+    /// Byte offset in the code the token comes from.
+    pub offset: u32,
+}
+
+impl TreeToken {
+    #[must_use]
+    pub fn new(text: impl Into<String>, offset: u32) -> Self {
+        Self { text: text.into(), offset }
+    }
+}
+
+/// Compares the trees of `src` and its formatted version `code`, flattened to tokens,
+/// reporting the first difference in both texts otherwise, located in `src` when it is there.
+pub fn compare_trees(
+    language: &str,
+    src: &str,
+    before: &[TreeToken],
+    code: &str,
+    after: &[TreeToken],
+) -> Result<(), SourceError> {
+    let Some(index) = before
+        .iter()
+        .zip(after)
+        .position(|(a, b)| a.text != b.text)
+        .or_else(|| (before.len() != after.len()).then_some(before.len().min(after.len())))
+    else {
+        return Ok(());
+    };
+
+    let at = |src: &str, token: &TreeToken| {
+        let Location { line, column } = Location::from_offset(src, token.offset as usize);
+        format!("`{}` at {line}:{column}", token.text)
+    };
+    let change = match (before.get(index), after.get(index)) {
+        (Some(a), Some(b)) => {
+            format!("{} of the source became {} of the output", at(src, a), at(code, b))
+        }
+        (Some(a), None) => format!("{} of the source is missing from the output", at(src, a)),
+        (None, Some(b)) => format!("{} of the output is not in the source", at(code, b)),
+        (None, None) => unreachable!(),
+    };
+
+    let message = format!("formatting changed the meaning of the {language} code: {change}");
+    Err(match before.get(index) {
+        Some(token) => SourceError::at(message, src, token.offset as usize),
+        None => message.into(),
+    })
+}
+
+/// The value of a string literal quoted with `'` or `"`, keeping other escapes as they are.
+#[must_use]
+pub fn unquote(literal: &str) -> String {
+    let inner = match literal.chars().next() {
+        Some(quote @ ('"' | '\'')) if literal.len() >= 2 && literal.ends_with(quote) => {
+            &literal[1..literal.len() - 1]
+        }
+        _ => literal,
+    };
+
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(quote @ ('"' | '\''))) => {
+                value.push(quote);
+                chars.next();
+            }
+            ('\\', Some(next)) => {
+                value.push(c);
+                value.push(next);
+                chars.next();
+            }
+            _ => value.push(c),
+        }
+    }
+    value
+}
+
+/// A canonical spelling of a decimal number literal, e.g. `.50E1` and `5.0` both give `5`.
+///
+/// Other literals, e.g. hexadecimal or with a unit, are only lowercased.
+#[must_use]
+pub fn normalize_number(literal: &str) -> String {
+    let digits = literal.replace('_', "");
+    let unsigned = digits.trim_start_matches(['+', '-']);
+    let decimal = unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && !["0x", "0X", "0b", "0B", "0o", "0O"].iter().any(|prefix| unsigned.starts_with(prefix))
+        && unsigned.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+
+    match digits.parse::<f64>() {
+        Ok(value) if decimal => value.to_string(),
+        _ => literal.to_ascii_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_normalized_tokens() {
+        assert_eq!(unquote(r#"'it\'s "a"'"#), r#"it's "a""#);
+        assert_eq!(unquote(r#""it's \"a\"\n""#), r#"it's "a"\n"#);
+        assert_eq!(normalize_number(".50E1"), normalize_number("5.0"));
+        assert_eq!(normalize_number("-1.0"), "-1");
+        assert_eq!(normalize_number("0XFF"), "0xff");
+
+        let tokens = |texts: &[&str]| {
+            texts
+                .iter()
+                .enumerate()
+                .map(|(i, &text)| TreeToken::new(text, i as u32 * 2))
+                .collect::<Vec<_>>()
+        };
+        assert!(
+            compare_trees("script", "a b", &tokens(&["a", "b"]), "a b", &tokens(&["a", "b"]))
+                .is_ok()
+        );
+        assert_eq!(
+            compare_trees("script", "a\nb", &tokens(&["a", "b"]), "a c", &tokens(&["a", "c"])),
+            Err(SourceError {
+                message: "formatting changed the meaning of the script code: `b` at 2:1 of the source became `c` at 1:3 of the output".into(),
+                location: Some(Location { line: 2, column: 1 }),
+            })
+        );
+    }
+}
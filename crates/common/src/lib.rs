@@ -1,7 +1,26 @@
+#[cfg(feature = "component")]
+pub mod component;
+mod diff;
+mod encoding;
+mod equivalence;
+mod ranges;
+#[cfg(feature = "serde")]
+mod sort_imports;
+
 use std::str::FromStr;
 
 #[cfg(feature = "serde")]
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+pub use diff::{DiffOptions, map_offset, map_offset_with_markers, text_edits, unified_diff};
+pub use encoding::{
+    EncodedFormattedRanges, EncodedFormattedWithCursor, EncodedPrintedRange, EncodedRange,
+    OffsetConverter, OffsetEncoding, Position,
+};
+pub use equivalence::{TreeToken, compare_trees, normalize_number, unquote};
+pub use ranges::{AnyRange, FormattedRanges, LineRange, format_ranges};
+#[cfg(feature = "serde")]
+pub use sort_imports::{CustomGroupDefinition, ParsedGroups, SortImportsOptions, SortOrder};
 
 #[cfg_attr(feature = "serde", derive(Deserialize))]
 #[derive(Clone, Default)]
@@ -150,3 +169,244 @@ impl FromStr for LineEnding {
         }
     }
 }
+
+/// A range in text, using UTF-8 byte offsets.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl TextRange {
+    /// The range covering all of `src`.
+    #[must_use]
+    pub fn whole(src: &str) -> Self {
+        Self { start: 0, end: src.len() as u32 }
+    }
+
+    /// Checks that the range is ordered, lies within `src` and starts and ends on char boundaries.
+    pub fn check(&self, src: &str) -> Result<(), String> {
+        let (start, end) = (self.start as usize, self.end as usize);
+        if start > end || !src.is_char_boundary(start) || !src.is_char_boundary(end) {
+            return Err(format!("range {start}..{end} is out of bounds"));
+        }
+        Ok(())
+    }
+
+    /// Whether the range touches `start..end`. An empty range touches the spans it lies within or at the edge of.
+    #[must_use]
+    pub fn touches(&self, start: usize, end: usize) -> bool {
+        let (range_start, range_end) = (self.start as usize, self.end as usize);
+        if range_start == range_end {
+            start <= range_start && range_start <= end
+        } else {
+            start < range_end && range_start < end
+        }
+    }
+}
+
+/// Result of formatting a range of code: the new text and the source range it replaces.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct PrintedRange {
+    pub code: String,
+    /// The replaced range, `None` with an empty `code` when the range touches nothing to format.
+    pub range: Option<TextRange>,
+}
+
+impl PrintedRange {
+    /// Replaces `start..end` of `src` with `code`, formatted on its own, indented by `indent`.
+    ///
+    /// When only whitespace precedes `start` on its line, the replaced range starts at the line,
+    /// so that the first line gets the same indentation as the others.
+    #[must_use]
+    pub fn indented(src: &str, start: usize, end: usize, code: &str, indent: &str) -> Self {
+        let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let own_line = src[line_start..start].trim().is_empty();
+        let start = if own_line { line_start } else { start };
+
+        let mut printed = String::with_capacity(code.len());
+        for (i, line) in code.trim().split('\n').enumerate() {
+            if i > 0 {
+                printed.push('\n');
+            }
+            if (i > 0 || own_line) && !line.trim().is_empty() {
+                printed.push_str(indent);
+            }
+            printed.push_str(line);
+        }
+
+        Self { code: printed, range: Some(TextRange { start: start as u32, end: end as u32 }) }
+    }
+}
+
+/// Result of formatting with a cursor: the formatted code and the cursor offset within it.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug)]
+pub struct FormattedWithCursor {
+    pub code: String,
+    pub cursor_offset: u32,
+}
+
+impl FormattedWithCursor {
+    /// Checks that `cursor_offset` lies within `src` and on a char boundary.
+    pub fn check_cursor(src: &str, cursor_offset: u32) -> Result<(), String> {
+        if !src.is_char_boundary(cursor_offset as usize) {
+            return Err(format!("cursor offset {cursor_offset} is out of bounds"));
+        }
+        Ok(())
+    }
+
+    /// Maps `cursor_offset` in `src` into `code` by diffing the two.
+    #[must_use]
+    pub fn from_diff(src: &str, code: String, cursor_offset: u32) -> Self {
+        let cursor_offset = map_offset(src, &code, cursor_offset as usize) as u32;
+        Self { code, cursor_offset }
+    }
+}
+
+/// A replacement of a range of the source by new text, the source range given in both UTF-8 and UTF-16 offsets.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub utf16_range: TextRange,
+    pub new_text: String,
+}
+
+/// A 1-based line and column, the column counted in characters.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Location {
+    /// The location of the UTF-8 byte `offset` of `src`.
+    #[must_use]
+    pub fn from_offset(src: &str, offset: usize) -> Self {
+        let line_start = src[..offset].rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: src[..offset].matches('\n').count() as u32 + 1,
+            column: src[line_start..offset].chars().count() as u32 + 1,
+        }
+    }
+}
+
+/// An error of formatting, with the location of the source it points at, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceError {
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl SourceError {
+    /// An error pointing at the UTF-8 byte `offset` of `src`.
+    #[must_use]
+    pub fn at(message: String, src: &str, offset: usize) -> Self {
+        Self { message, location: Some(Location::from_offset(src, offset)) }
+    }
+}
+
+impl std::fmt::Display for SourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for SourceError {
+    fn from(message: String) -> Self {
+        Self { message, location: None }
+    }
+}
+
+impl From<&str> for SourceError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<SourceError> for String {
+    fn from(error: SourceError) -> Self {
+        error.message
+    }
+}
+
+/// Result of checking whether code is already formatted.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckResult {
+    pub formatted: bool,
+    /// Where the source first differs from the formatted code.
+    pub first_difference: Option<Location>,
+}
+
+impl CheckResult {
+    /// Compares `src` with its formatted version `code`.
+    #[must_use]
+    pub fn new(src: &str, code: &str) -> Self {
+        let Some(mut offset) = src
+            .bytes()
+            .zip(code.bytes())
+            .position(|(a, b)| a != b)
+            .or_else(|| (src.len() != code.len()).then_some(src.len().min(code.len())))
+        else {
+            return Self { formatted: true, first_difference: None };
+        };
+
+        while !src.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        Self { formatted: false, first_difference: Some(Location::from_offset(src, offset)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_formatted_code() {
+        assert_eq!(
+            CheckResult::new("a = 1;\n", "a = 1;\n"),
+            CheckResult { formatted: true, first_difference: None }
+        );
+    }
+
+    #[test]
+    fn locates_the_first_difference() {
+        let location = |line, column| Some(Location { line, column });
+
+        let result = CheckResult::new("a = 1;\nb=2;\n", "a = 1;\nb = 2;\n");
+        assert!(!result.formatted);
+        assert_eq!(result.first_difference, location(2, 2));
+
+        // Columns count characters, and a difference inside a char points at its start.
+        let result = CheckResult::new("x;\nconst é='ü';\n", "x;\nconst é = \"ü\";\n");
+        assert_eq!(result.first_difference, location(2, 8));
+        assert_eq!(CheckResult::new("s = 'é'", "s = 'ê'").first_difference, location(1, 6));
+
+        // A missing trailing newline differs at the end of the source.
+        assert_eq!(CheckResult::new("a;", "a;\n").first_difference, location(1, 3));
+    }
+
+    #[test]
+    fn indents_printed_ranges() {
+        let src = "a {\n    b { c: d }\n}\n";
+        let start = src.find('b').unwrap();
+        let end = src.find('}').unwrap() + 1;
+
+        let printed = PrintedRange::indented(src, start, end, "b {\n  c: d;\n\n  e: f;\n}\n", "  ");
+        assert_eq!(printed.code, "  b {\n    c: d;\n\n    e: f;\n  }");
+        assert_eq!(printed.range, Some(TextRange { start: 4, end: end as u32 }));
+
+        let printed = PrintedRange::indented(src, start + 4, end, "c: d;", "  ");
+        assert_eq!(printed.code, "c: d;");
+        assert_eq!(printed.range, Some(TextRange { start: start as u32 + 4, end: end as u32 }));
+    }
+}
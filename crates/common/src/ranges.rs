@@ -0,0 +1,187 @@
+//! Formatting several ranges of a document in one pass.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{EncodedRange, OffsetConverter, PrintedRange, TextRange};
+
+/// A range of whole lines, 1-based and inclusive, e.g. a diff hunk.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineRange {
+    #[cfg_attr(feature = "serde", serde(alias = "startLine"))]
+    pub start_line: u32,
+    #[cfg_attr(feature = "serde", serde(alias = "endLine"))]
+    pub end_line: u32,
+}
+
+impl LineRange {
+    /// The text range from the start of `start_line` to the end of `end_line`, without its line break.
+    pub fn to_text_range(&self, src: &str) -> Result<TextRange, String> {
+        if self.start_line == 0 || self.start_line > self.end_line {
+            return Err(format!("line range {}..{} is invalid", self.start_line, self.end_line));
+        }
+
+        let line_start = |line: u32| {
+            if line == 1 {
+                return Some(0);
+            }
+            src.match_indices('\n').nth(line as usize - 2).map(|(i, _)| i + 1)
+        };
+
+        let start = line_start(self.start_line).unwrap_or(src.len());
+        let end = line_start(self.end_line + 1)
+            .map_or(src.len(), |next| next - 1 - usize::from(src[..next - 1].ends_with('\r')));
+
+        Ok(TextRange { start: start as u32, end: end.max(start) as u32 })
+    }
+}
+
+/// A range to format, either in offsets or in lines.
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+#[derive(Clone, Copy, Debug)]
+pub enum AnyRange {
+    Text(EncodedRange),
+    Lines(LineRange),
+}
+
+impl AnyRange {
+    pub fn to_text_range(&self, offsets: &OffsetConverter) -> Result<TextRange, String> {
+        match self {
+            Self::Text(range) => offsets.range_to_utf8(*range),
+            Self::Lines(range) => range.to_text_range(offsets.src()),
+        }
+    }
+}
+
+/// Result of formatting several ranges: the whole new document and the edits it is made of.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct FormattedRanges {
+    pub code: String,
+    /// The formatted ranges in source order, with non-overlapping ranges of the original source.
+    pub ranges: Vec<PrintedRange>,
+}
+
+/// Formats several ranges of `src` with `format_range` without the edits clobbering each other.
+///
+/// Overlapping or adjacent ranges are merged first. When two ranges are expanded by the
+/// formatter to overlapping source ranges, they are formatted again as one.
+pub fn format_ranges(
+    src: &str,
+    ranges: &[TextRange],
+    mut format_range: impl FnMut(TextRange) -> Result<PrintedRange, String>,
+) -> Result<FormattedRanges, String> {
+    for range in ranges {
+        range.check(src)?;
+    }
+
+    let mut requests = merge(ranges.to_vec());
+    let mut cache = HashMap::new();
+
+    let printed = loop {
+        let mut printed: Vec<(TextRange, PrintedRange)> = vec![];
+        for &request in &requests {
+            let result = match cache.get(&request) {
+                Some(result) => PrintedRange::clone(result),
+                None => {
+                    let result = format_range(request)?;
+                    cache.insert(request, result.clone());
+                    result
+                }
+            };
+            if let Some(range) = result.range {
+                range.check(src)?;
+                printed.push((request, result));
+            }
+        }
+        printed.sort_by_key(|(_, printed)| printed.range.map(|range| range.start));
+
+        let overlap = printed.windows(2).find_map(|pair| {
+            let (first, second) = (pair[0].1.range?, pair[1].1.range?);
+            (second.start < first.end).then(|| TextRange {
+                start: pair[0].0.start.min(pair[1].0.start),
+                end: pair[0].0.end.max(pair[1].0.end),
+            })
+        });
+
+        match overlap {
+            Some(union) => {
+                requests.push(union);
+                requests = merge(requests);
+            }
+            None => break printed,
+        }
+    };
+
+    let mut code = String::with_capacity(src.len());
+    let mut last = 0;
+    for (_, printed) in &printed {
+        let Some(range) = printed.range else { continue };
+        code.push_str(&src[last..range.start as usize]);
+        code.push_str(&printed.code);
+        last = range.end as usize;
+    }
+    code.push_str(&src[last..]);
+
+    Ok(FormattedRanges { code, ranges: printed.into_iter().map(|(_, printed)| printed).collect() })
+}
+
+/// Sorts `ranges` and merges the overlapping or adjacent ones.
+fn merge(mut ranges: Vec<TextRange>) -> Vec<TextRange> {
+    ranges.sort_by_key(|range| (range.start, range.end));
+
+    let mut merged: Vec<TextRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_line_ranges() {
+        let src = "a\r\nbb\nccc";
+        let range = LineRange { start_line: 2, end_line: 2 }.to_text_range(src).unwrap();
+        assert_eq!(&src[range.start as usize..range.end as usize], "bb");
+
+        let range = LineRange { start_line: 1, end_line: 3 }.to_text_range(src).unwrap();
+        assert_eq!(&src[range.start as usize..range.end as usize], src);
+    }
+
+    #[test]
+    fn formats_overlapping_ranges_once() {
+        // Formats the whole line around the range in upper case.
+        let format_line = |range: TextRange| {
+            let src = "ab\ncd\nef";
+            let start = src[..range.start as usize].rfind('\n').map_or(0, |i| i + 1);
+            let end =
+                src[range.end as usize..].find('\n').map_or(src.len(), |i| range.end as usize + i);
+            Ok(PrintedRange {
+                code: src[start..end].to_uppercase(),
+                range: Some(TextRange { start: start as u32, end: end as u32 }),
+            })
+        };
+
+        let ranges = [
+            TextRange { start: 7, end: 8 },
+            TextRange { start: 0, end: 1 },
+            TextRange { start: 1, end: 2 },
+            TextRange { start: 6, end: 6 },
+        ];
+        let formatted = format_ranges("ab\ncd\nef", &ranges, format_line).unwrap();
+
+        assert_eq!(formatted.code, "AB\ncd\nEF");
+        assert_eq!(formatted.ranges.len(), 2);
+        assert_eq!(formatted.ranges[1].range, Some(TextRange { start: 6, end: 8 }));
+    }
+}
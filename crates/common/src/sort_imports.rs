@@ -0,0 +1,179 @@
+//! The `sortImports` config shared by the script formatters.
+
+use serde::{Deserialize, Deserializer};
+
+/// Options for sorting import statements, the `sortImports` config of the script formatters.
+///
+/// Group, selector and modifier names are kept as written; each formatter maps them to its own.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SortImportsOptions {
+    #[serde(default)]
+    pub partition_by_newline: bool,
+    #[serde(default)]
+    pub partition_by_comment: bool,
+    #[serde(default)]
+    pub sort_side_effects: bool,
+    #[serde(default)]
+    pub order: SortOrder,
+    #[serde(default = "default_true")]
+    pub ignore_case: bool,
+    #[serde(default = "default_true")]
+    pub newlines_between: bool,
+    #[serde(default = "default_internal_patterns")]
+    pub internal_pattern: Vec<String>,
+    #[serde(default = "default_groups", deserialize_with = "groups::deserialize")]
+    pub groups: ParsedGroups,
+    #[serde(default)]
+    pub custom_groups: Vec<CustomGroupDefinition>,
+}
+
+impl Default for SortImportsOptions {
+    fn default() -> Self {
+        Self {
+            partition_by_newline: false,
+            partition_by_comment: false,
+            sort_side_effects: false,
+            order: SortOrder::default(),
+            ignore_case: true,
+            newlines_between: true,
+            internal_pattern: default_internal_patterns(),
+            groups: default_groups(),
+            custom_groups: vec![],
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Parsed groups result containing both groups and newline boundary overrides.
+#[derive(Default, Clone, Debug)]
+pub struct ParsedGroups {
+    pub groups: Vec<Vec<String>>,
+    pub newline_boundary_overrides: Vec<Option<bool>>,
+}
+
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomGroupDefinition {
+    #[serde(default)]
+    pub group_name: String,
+    #[serde(default)]
+    pub element_name_pattern: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_selector")]
+    pub selector: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_modifiers")]
+    pub modifiers: Vec<String>,
+}
+
+const SELECTORS: &[&str] = &["value", "type", "default", "namespace", "side-effect"];
+const MODIFIERS: &[&str] = &["type", "value", "default", "namespace", "named", "side-effect"];
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_internal_patterns() -> Vec<String> {
+    vec!["~/".into(), "@/".into()]
+}
+
+fn default_groups() -> ParsedGroups {
+    let groups = [
+        &["type-import"][..],
+        &["value-builtin", "value-external"],
+        &["type-internal"],
+        &["value-internal"],
+        &["type-parent", "type-sibling", "type-index"],
+        &["value-parent", "value-sibling", "value-index"],
+        &["unknown"],
+    ];
+
+    ParsedGroups {
+        groups: groups
+            .iter()
+            .map(|group| group.iter().map(|entry| (*entry).to_string()).collect())
+            .collect(),
+        newline_boundary_overrides: vec![],
+    }
+}
+
+fn deserialize_selector<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    match value {
+        Some(s) if !SELECTORS.contains(&s.as_str()) => {
+            Err(serde::de::Error::custom(format!("Invalid import selector: {s}")))
+        }
+        other => Ok(other),
+    }
+}
+
+fn deserialize_modifiers<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Vec::<String>::deserialize(deserializer)?;
+    if let Some(invalid) = values.iter().find(|s| !MODIFIERS.contains(&s.as_str())) {
+        return Err(serde::de::Error::custom(format!("Invalid import modifier: {invalid}")));
+    }
+    Ok(values)
+}
+
+/// Custom deserialization module for groups
+mod groups {
+    use serde::Deserialize;
+    use serde::Deserializer;
+
+    use super::ParsedGroups;
+
+    /// A marker object for overriding `newlinesBetween` at a specific group boundary.
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct NewlinesBetweenMarker {
+        newlines_between: bool,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum GroupItem {
+        NewlinesBetween(NewlinesBetweenMarker),
+        Single(String),
+        Multiple(Vec<String>),
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<ParsedGroups, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let items: Vec<GroupItem> = Vec::deserialize(deserializer)?;
+
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        let mut newline_boundary_overrides: Vec<Option<bool>> = Vec::new();
+        let mut pending_override: Option<bool> = None;
+
+        for item in items {
+            let entries = match item {
+                GroupItem::NewlinesBetween(marker) => {
+                    pending_override = Some(marker.newlines_between);
+                    continue;
+                }
+                GroupItem::Single(s) => vec![s],
+                GroupItem::Multiple(v) => v,
+            };
+            if !groups.is_empty() {
+                newline_boundary_overrides.push(pending_override.take());
+            }
+            groups.push(entries);
+        }
+
+        Ok(ParsedGroups { groups, newline_boundary_overrides })
+    }
+}
@@ -3,7 +3,7 @@
 import source wasmModule from "./graphql_fmt_bg.wasm";
 
 import * as import_bg from "./graphql_fmt_bg.js";
-const { __wbg_set_wasm, format, ...wasmImport } = import_bg;
+const { __wbg_set_wasm, format, formatRange, formatWithCursor, formatEdits, check, ...wasmImport } = import_bg;
 
 function getImports() {
 	return {
@@ -24,4 +24,4 @@ const instance = new WebAssembly.Instance(wasmModule, getImports());
 const wasm = instance.exports;
 __wbg_set_wasm(wasm);
 
-export { format };
+export { format, formatRange, formatWithCursor, formatEdits, check };
@@ -1,5 +1,13 @@
 import type { LayoutConfig } from "./layout_config.d.ts";
 
+export interface CanonicalizeOptions {
+	/** Sort the fields of selection sets alphabetically, before fragment spreads. Defaults to false. */
+	sortSelections?: boolean;
+
+	/** Sort field and directive arguments by name. Defaults to false. */
+	sortArguments?: boolean;
+}
+
 /**
  * Configuration options for GraphQL formatter.
  *
@@ -150,6 +158,20 @@ export interface Config extends LayoutConfig {
 	/** Directive to ignore comments. Defaults to "pretty-graphql-ignore". */
 	ignoreCommentDirective?: string;
 
+	/** Print the document on a single line instead of pretty printing. Kept comments end their line. Defaults to false. */
+	compact?: boolean;
+
+	/** Remove comments in compact output. Defaults to false. */
+	stripComments?: boolean;
+
+	/**
+	 * Rewrite the document into a canonical form before printing: top-level definitions are sorted
+	 * by kind and name, duplicate fragment definitions removed and descriptions normalized. Comments
+	 * move with the definition, selection or argument they precede or end the line of.
+	 * Conflicting fragment definitions are an error. Defaults to false.
+	 */
+	canonicalize?: boolean | CanonicalizeOptions;
+
 	/**
 	 *@see {@link https://pretty-graphql.netlify.app/}
 	 */
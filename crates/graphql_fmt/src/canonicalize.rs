@@ -0,0 +1,463 @@
+//! Canonical form of GraphQL documents, e.g. for persisted queries and schema diffing.
+
+use std::fmt::Write;
+
+use apollo_parser::{
+    Parser, SyntaxKind, SyntaxNode,
+    cst::{CstNode, Definition},
+};
+
+use crate::{
+    config::CanonicalizeOptions,
+    lexer::{TokenKind, block_string_value, tokenize},
+};
+
+/// Rewrites `src` into its canonical form, to be printed by pretty_graphql afterwards.
+///
+/// Top-level definitions are sorted by kind and name, identical fragment definitions are
+/// deduplicated and descriptions are normalized. Comments move along with the definition,
+/// selection or argument they precede, or whose line they end.
+///
+/// Returns `Ok(None)` when `src` has syntax errors, leaving them to the formatter.
+pub(crate) fn canonicalize(
+    src: &str,
+    options: &CanonicalizeOptions,
+) -> Result<Option<String>, String> {
+    let tree = Parser::new(src).parse();
+    if tree.errors().next().is_some() {
+        return Ok(None);
+    }
+
+    let nodes: Vec<_> = tree.document().definitions().collect();
+    let Some(chunks) = chunks(src, nodes.iter().map(CstNode::syntax), (0, src.len())) else {
+        return Ok(None);
+    };
+
+    let mut definitions: Vec<_> = nodes
+        .iter()
+        .zip(chunks.items)
+        .map(|(definition, chunk)| {
+            let executable = matches!(
+                definition,
+                Definition::OperationDefinition(_) | Definition::FragmentDefinition(_)
+            );
+            let rewriter = Rewriter { src, options, executable };
+            (definition_key(definition), rewriter.chunk_text(definition.syntax(), chunk))
+        })
+        .collect();
+
+    definitions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut out = String::with_capacity(src.len());
+    let mut previous: Option<&((Rank, String), String)> = None;
+    for definition in &definitions {
+        if let Some(previous) = previous
+            && definition.0.0 == Rank::Fragment
+            && previous.0 == definition.0
+        {
+            if significant_text(&previous.1) == significant_text(&definition.1) {
+                continue;
+            }
+            return Err(format!("conflicting definitions of fragment `{}`", definition.0.1));
+        }
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        out.push_str(&definition.1);
+        previous = Some(definition);
+    }
+    if let Some(dangling) = chunks.dangling {
+        out.push_str("\n\n");
+        out.push_str(&src[dangling.0..dangling.1]);
+    }
+    out.push('\n');
+
+    Ok(Some(out))
+}
+
+/// The source range of `node` from its first to its last token, without the whitespace,
+/// commas and comments around it.
+pub(crate) fn bounds(node: &SyntaxNode) -> Option<(usize, usize)> {
+    let mut tokens =
+        node.descendants_with_tokens().filter_map(|element| element.into_token()).filter(|token| {
+            !matches!(
+                token.kind(),
+                SyntaxKind::WHITESPACE | SyntaxKind::COMMA | SyntaxKind::COMMENT
+            )
+        });
+
+    let first = tokens.next()?;
+    let last = tokens.last().unwrap_or_else(|| first.clone());
+    Some((usize::from(first.text_range().start()), usize::from(last.text_range().end())))
+}
+
+/// A node together with the comments that move along with it.
+#[derive(Clone, Copy)]
+struct Chunk {
+    /// Start of the comments on the lines before the node, or of the node itself.
+    start: usize,
+    /// Bounds of the node.
+    node: (usize, usize),
+    /// End of the comments on the last line of the node, or of the node itself.
+    end: usize,
+}
+
+struct Chunks {
+    items: Vec<Chunk>,
+    /// The comments after the last item.
+    dangling: Option<(usize, usize)>,
+}
+
+/// Splits the comments between `nodes`, which lie within `outer` in order: comments on the line
+/// where a node ends trail it, and the others lead the node after them.
+fn chunks<'a>(
+    src: &str,
+    nodes: impl Iterator<Item = &'a SyntaxNode>,
+    outer: (usize, usize),
+) -> Option<Chunks> {
+    let nodes: Vec<_> = nodes.map(bounds).collect::<Option<_>>()?;
+    let mut items = Vec::with_capacity(nodes.len());
+    let mut from = outer.0;
+
+    for (index, &node) in nodes.iter().enumerate() {
+        let start = src[from..node.0].find('#').map_or(node.0, |index| from + index);
+
+        let after = &src[node.1..nodes.get(index + 1).map_or(outer.1, |next| next.0)];
+        let line = &after[..after.find('\n').unwrap_or(after.len())];
+        let end =
+            line.find('#').map_or(node.1, |index| node.1 + index + line[index..].trim_end().len());
+
+        items.push(Chunk { start, node, end });
+        from = end;
+    }
+
+    let dangling = src[from..outer.1]
+        .find('#')
+        .map(|index| (from + index, from + src[from..outer.1].trim_end().len()));
+
+    Some(Chunks { items, dangling })
+}
+
+struct Rewriter<'a> {
+    src: &'a str,
+    options: &'a CanonicalizeOptions,
+    /// Whether the node belongs to an operation or a fragment, whose selections and
+    /// arguments may be sorted.
+    executable: bool,
+}
+
+impl Rewriter<'_> {
+    /// The canonical text of `node` with its comments.
+    fn chunk_text(&self, node: &SyntaxNode, chunk: Chunk) -> String {
+        let (start, end) = chunk.node;
+        format!(
+            "{}{}{}",
+            &self.src[chunk.start..start],
+            self.node_text(node),
+            &self.src[end..chunk.end]
+        )
+    }
+
+    /// The canonical text of `node`, keeping the source text between its children.
+    fn node_text(&self, node: &SyntaxNode) -> String {
+        let Some((start, end)) = bounds(node) else {
+            return String::new();
+        };
+
+        let mut out = String::with_capacity(end - start);
+        let mut position = start;
+        for child in node.children() {
+            let Some((child_start, child_end)) = bounds(&child) else {
+                continue;
+            };
+            let text = match child.kind() {
+                SyntaxKind::DESCRIPTION => normalize_description(&self.src[child_start..child_end]),
+                SyntaxKind::SELECTION_SET if self.executable && self.options.sort_selections() => {
+                    self.sorted_children(&child, selection_key)
+                }
+                SyntaxKind::ARGUMENTS if self.executable && self.options.sort_arguments() => {
+                    self.sorted_children(&child, argument_key)
+                }
+                _ => None,
+            };
+
+            out.push_str(&self.src[position..child_start]);
+            out.push_str(&text.unwrap_or_else(|| self.node_text(&child)));
+            position = child_end;
+        }
+        out.push_str(&self.src[position..end]);
+
+        out
+    }
+
+    /// Prints the children of a bracketed node, e.g. a selection set, one per line in the
+    /// order of `key`, each with its comments.
+    fn sorted_children<K: Ord>(
+        &self,
+        node: &SyntaxNode,
+        key: impl Fn(&SyntaxNode, &str) -> K,
+    ) -> Option<String> {
+        let (start, end) = bounds(node)?;
+        let children: Vec<_> = node.children().collect();
+        let chunks = chunks(self.src, children.iter(), (start + 1, end - 1))?;
+
+        let mut items: Vec<_> = children
+            .iter()
+            .zip(chunks.items)
+            .map(|(child, chunk)| {
+                let text = self.node_text(child);
+                (key(child, &text), self.chunk_text(child, chunk))
+            })
+            .collect();
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut out = String::with_capacity(end - start);
+        out.push_str(&self.src[start..start + 1]);
+        for (_, text) in &items {
+            out.push('\n');
+            out.push_str(text);
+        }
+        if let Some((dangling_start, dangling_end)) = chunks.dangling {
+            out.push('\n');
+            out.push_str(&self.src[dangling_start..dangling_end]);
+        }
+        out.push('\n');
+        out.push_str(&self.src[end - 1..end]);
+
+        Some(out)
+    }
+}
+
+/// Fields come before fragment spreads and inline fragments, each sorted by their text.
+fn selection_key(node: &SyntaxNode, text: &str) -> (bool, String) {
+    let is_fragment =
+        matches!(node.kind(), SyntaxKind::FRAGMENT_SPREAD | SyntaxKind::INLINE_FRAGMENT);
+    (is_fragment, significant_text(text))
+}
+
+fn argument_key(node: &SyntaxNode, _text: &str) -> Option<String> {
+    name_of(node)
+}
+
+/// The tokens of `text` separated by single spaces, without comments.
+fn significant_text(text: &str) -> String {
+    let tokens: Vec<_> = tokenize(text)
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .map(|token| token.text)
+        .collect();
+    tokens.join(" ")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    Schema,
+    Directive,
+    Scalar,
+    Interface,
+    Type,
+    Union,
+    Enum,
+    Input,
+    Extension,
+    Operation,
+    Fragment,
+}
+
+fn definition_key(definition: &Definition) -> (Rank, String) {
+    let (rank, extended) = match definition {
+        Definition::SchemaDefinition(_) => (Rank::Schema, None),
+        Definition::DirectiveDefinition(_) => (Rank::Directive, None),
+        Definition::ScalarTypeDefinition(_) => (Rank::Scalar, None),
+        Definition::InterfaceTypeDefinition(_) => (Rank::Interface, None),
+        Definition::ObjectTypeDefinition(_) => (Rank::Type, None),
+        Definition::UnionTypeDefinition(_) => (Rank::Union, None),
+        Definition::EnumTypeDefinition(_) => (Rank::Enum, None),
+        Definition::InputObjectTypeDefinition(_) => (Rank::Input, None),
+        Definition::SchemaExtension(_) => (Rank::Extension, Some("schema")),
+        Definition::ScalarTypeExtension(_) => (Rank::Extension, Some("scalar")),
+        Definition::InterfaceTypeExtension(_) => (Rank::Extension, Some("interface")),
+        Definition::ObjectTypeExtension(_) => (Rank::Extension, Some("type")),
+        Definition::UnionTypeExtension(_) => (Rank::Extension, Some("union")),
+        Definition::EnumTypeExtension(_) => (Rank::Extension, Some("enum")),
+        Definition::InputObjectTypeExtension(_) => (Rank::Extension, Some("input")),
+        Definition::OperationDefinition(_) => (Rank::Operation, None),
+        Definition::FragmentDefinition(_) => (Rank::Fragment, None),
+    };
+
+    // Anonymous operations and schema definitions have no name.
+    let name = name_of(definition.syntax()).unwrap_or_default();
+
+    // `extend type Foo` is keyed by both the extended kind and the name.
+    match extended {
+        Some(kind) => (rank, format!("{kind} {name}").trim_end().to_string()),
+        None => (rank, name),
+    }
+}
+
+/// The name of a definition, a fragment or an argument.
+fn name_of(node: &SyntaxNode) -> Option<String> {
+    let name = node.children().find_map(|child| match child.kind() {
+        SyntaxKind::NAME => Some(child),
+        SyntaxKind::FRAGMENT_NAME => child.children().find(|name| name.kind() == SyntaxKind::NAME),
+        _ => None,
+    })?;
+
+    Some(name.first_token()?.text().to_string())
+}
+
+/// Prints a description as a single-line string, or as a block string if it has line breaks.
+fn normalize_description(raw: &str) -> Option<String> {
+    let value = if let Some(block) = raw.strip_prefix("\"\"\"") {
+        let inner = block.strip_suffix("\"\"\"")?;
+        block_string_value(&inner.replace("\\\"\"\"", "\"\"\""))
+    } else {
+        decode_string(raw.strip_prefix('"')?.strip_suffix('"')?)?
+    };
+
+    let mut out = String::with_capacity(value.len() + 8);
+    if value.contains('\n') {
+        out.push_str("\"\"\"\n");
+        out.push_str(&value.replace("\"\"\"", "\\\"\"\""));
+        out.push_str("\n\"\"\"");
+    } else {
+        out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if c < '\u{20}' => {
+                    let _ = write!(out, "\\u{:04X}", c as u32);
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    Some(out)
+}
+
+fn decode_string(raw: &str) -> Option<String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '"' => out.push('"'),
+            '\\' => out.push('\\'),
+            '/' => out.push('/'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            'u' => {
+                let rest = chars.as_str();
+                let code = if let Some(braced) = rest.strip_prefix('{') {
+                    let end = braced.find('}')?;
+                    let code = u32::from_str_radix(&braced[..end], 16).ok()?;
+                    chars = braced[end + 1..].chars();
+                    code
+                } else {
+                    let high = u32::from_str_radix(rest.get(..4)?, 16).ok()?;
+                    chars = rest[4..].chars();
+                    if (0xd800..0xdc00).contains(&high) {
+                        let low = chars.as_str().strip_prefix("\\u")?.get(..4)?;
+                        let low = u32::from_str_radix(low, 16).ok()?;
+                        chars = chars.as_str()[6..].chars();
+                        0x10000 + ((high - 0xd800) << 10) + (low.checked_sub(0xdc00)?)
+                    } else {
+                        high
+                    }
+                };
+                out.push(char::from_u32(code)?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(sort: bool) -> CanonicalizeOptions {
+        CanonicalizeOptions { sort_selections: Some(sort), sort_arguments: Some(sort) }
+    }
+
+    #[test]
+    fn sorts_definitions_and_dedupes_fragments() {
+        let src = r#"
+fragment B on T { b }
+query Q($v: Int, $a: Int) { ...B user(name: "x", id: 1) { name id } }
+fragment A on T { a }
+fragment B on T {
+  b
+}
+"#;
+
+        assert_eq!(
+            canonicalize(src, &options(false)).unwrap().unwrap(),
+            "query Q($v: Int, $a: Int) { ...B user(name: \"x\", id: 1) { name id } }\n\n\
+             fragment A on T { a }\n\n\
+             fragment B on T { b }\n"
+        );
+        assert_eq!(
+            canonicalize(src, &options(true)).unwrap().unwrap().split("\n\n").next(),
+            Some(
+                "query Q($v: Int, $a: Int) {\nuser(\nid: 1\nname: \"x\"\n) {\nid\nname\n}\n...B\n}"
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_fragments() {
+        let src = "fragment A on T { a } fragment A on T { b }";
+
+        assert_eq!(
+            canonicalize(src, &options(false)).unwrap_err(),
+            "conflicting definitions of fragment `A`"
+        );
+    }
+
+    #[test]
+    fn normalizes_descriptions() {
+        let src = "\"\"\"\n    Single line\n  \"\"\"\ntype B { \"\"\"\n  first\n    second\n  \"\"\" b(x: String = \"\\u0041\"): Int }\n\"A \\u0041\" scalar A";
+
+        assert_eq!(
+            canonicalize(src, &options(false)).unwrap().unwrap(),
+            "\"A A\" scalar A\n\n\"Single line\"\ntype B { \"\"\"\nfirst\n  second\n\"\"\" b(x: String = \"\\u0041\"): Int }\n"
+        );
+    }
+
+    #[test]
+    fn sorts_body_less_types() {
+        let src = "type Query @key\nquery { a }\ninterface Node\nextend type Query\n";
+
+        assert_eq!(
+            canonicalize(src, &options(false)).unwrap().unwrap(),
+            "interface Node\n\ntype Query @key\n\nextend type Query\n\nquery { a }\n"
+        );
+        // A `{` after a type is its body, which can't hold a selection.
+        assert!(canonicalize("type Query\n{ a }\n", &options(false)).unwrap().is_none());
+    }
+
+    #[test]
+    fn keeps_comments() {
+        let src = "# the query\nquery Q {\n  b # about b\n  # before a\n  a\n  # last\n}\n# type A\ntype A { a: Int } # trailing\n# end\n";
+
+        assert_eq!(
+            canonicalize(src, &options(true)).unwrap().unwrap(),
+            "# type A\ntype A { a: Int } # trailing\n\n\
+             # the query\nquery Q {\n# before a\n  a\nb # about b\n# last\n}\n\n\
+             # end\n"
+        );
+    }
+}
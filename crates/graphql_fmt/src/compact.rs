@@ -0,0 +1,52 @@
+//! Compact (single-line) output for GraphQL documents.
+
+use crate::lexer::{TokenKind, is_name_char, tokenize};
+
+/// Re-emits the tokens of `src` separated by a space only where the lexer needs one.
+///
+/// Commas are insignificant in GraphQL and are dropped. Comments run to the end of the line,
+/// so unless `strip_comments` is set each kept comment is followed by a line break.
+pub(crate) fn compact(src: &str, strip_comments: bool) -> String {
+    let mut out = String::with_capacity(src.len());
+
+    for token in tokenize(src) {
+        if token.kind == TokenKind::Comment {
+            if !strip_comments {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push(' ');
+                }
+                out.push_str(token.text);
+                out.push('\n');
+            }
+            continue;
+        }
+
+        if out.ends_with(is_name_char)
+            && token.text.starts_with(|c: char| is_name_char(c) || c == '.')
+        {
+            out.push(' ');
+        }
+        out.push_str(token.text);
+    }
+
+    while out.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compact;
+
+    #[test]
+    fn prints_a_single_line() {
+        let src = "# get hero\nquery Hero($episode: Episode = JEDI, $first: Int = -1) {\n  hero(episode: $episode) {\n    name\n    ...Friends\n    ... on Droid { primaryFunction }\n  }\n}\n";
+
+        assert_eq!(
+            compact(src, true),
+            "query Hero($episode:Episode=JEDI$first:Int=-1){hero(episode:$episode){name ...Friends ...on Droid{primaryFunction}}}"
+        );
+        assert_eq!(compact(src, false).lines().next(), Some("# get hero"));
+    }
+}
@@ -82,6 +82,52 @@ pub struct GraphqlConfig {
 
     #[serde(flatten)]
     language: LanguageOptions,
+
+    /// Print the document on a single line instead of pretty printing. Defaults to false.
+    #[serde(default)]
+    compact: Option<bool>,
+
+    /// Remove comments in compact output. Defaults to false.
+    #[serde(default, alias = "stripComments")]
+    strip_comments: Option<bool>,
+
+    /// Rewrite the document into a canonical form before printing. Defaults to false.
+    #[serde(default)]
+    canonicalize: Option<Canonicalize>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+enum Canonicalize {
+    Enabled(bool),
+    Options(CanonicalizeOptions),
+}
+
+/// Options of the canonical form.
+///
+/// Top-level definitions are always sorted by kind and name, duplicate fragment definitions
+/// removed and descriptions normalized.
+#[derive(Deserialize, Default, Clone)]
+pub struct CanonicalizeOptions {
+    /// Sort the fields of selection sets alphabetically, before fragment spreads. Defaults to false.
+    #[serde(default, alias = "sortSelections")]
+    pub(crate) sort_selections: Option<bool>,
+
+    /// Sort field and directive arguments by name. Defaults to false.
+    #[serde(default, alias = "sortArguments")]
+    pub(crate) sort_arguments: Option<bool>,
+}
+
+impl CanonicalizeOptions {
+    #[must_use]
+    pub fn sort_selections(&self) -> bool {
+        self.sort_selections.unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn sort_arguments(&self) -> bool {
+        self.sort_arguments.unwrap_or_default()
+    }
 }
 
 impl GraphqlConfig {
@@ -96,6 +142,26 @@ impl GraphqlConfig {
         self.layout = self.layout.fill_empty_with(layout);
         self
     }
+
+    #[must_use]
+    pub fn compact(&self) -> bool {
+        self.compact.unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn strip_comments(&self) -> bool {
+        self.strip_comments.unwrap_or_default()
+    }
+
+    /// The canonical form options, `None` when canonicalization is disabled.
+    #[must_use]
+    pub fn canonicalize(&self) -> Option<CanonicalizeOptions> {
+        match &self.canonicalize {
+            Some(Canonicalize::Enabled(true)) => Some(CanonicalizeOptions::default()),
+            Some(Canonicalize::Options(options)) => Some(options.clone()),
+            Some(Canonicalize::Enabled(false)) | None => None,
+        }
+    }
 }
 
 impl From<GraphqlConfig> for FormatOptions {
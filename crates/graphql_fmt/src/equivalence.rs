@@ -0,0 +1,77 @@
+//! Checks that formatting keeps the syntax tree of GraphQL documents.
+
+use common::{SourceError, TreeToken, compare_trees};
+
+use crate::{
+    canonicalize,
+    config::GraphqlConfig,
+    lexer::{Token, TokenKind, block_string_value, tokenize},
+};
+
+/// Checks that `code`, the formatted version of `src`, has the same tokens.
+///
+/// Whitespace, commas, comments and the indentation of block strings are ignored.
+/// When `config` canonicalizes documents, the source is compared in its canonical form.
+pub fn verify_graphql_equivalence(
+    src: &str,
+    code: &str,
+    config: &GraphqlConfig,
+) -> Result<(), SourceError> {
+    let canonical = match config.canonicalize() {
+        Some(options) => canonicalize::canonicalize(src, &options)?,
+        None => None,
+    };
+    let src = canonical.as_deref().unwrap_or(src);
+
+    compare_trees("GraphQL", src, &tree_tokens(src), code, &tree_tokens(code))
+}
+
+fn tree_tokens(src: &str) -> Vec<TreeToken> {
+    tokenize(src)
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .map(|token| TreeToken::new(normalize(&token), offset_of(src, token.text)))
+        .collect()
+}
+
+fn normalize(token: &Token) -> String {
+    match token.kind {
+        TokenKind::BlockString => {
+            let inner =
+                token.text.strip_prefix("\"\"\"").and_then(|raw| raw.strip_suffix("\"\"\""));
+            format!("\"\"\"{}\"\"\"", block_string_value(inner.unwrap_or(token.text)))
+        }
+        _ => token.text.to_string(),
+    }
+}
+
+/// Byte offset of a slice borrowed from `src`.
+fn offset_of(src: &str, slice: &str) -> u32 {
+    (slice.as_ptr() as usize - src.as_ptr() as usize) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_unchanged_tokens() {
+        let src = "query { a(x: 1, y: \"z\") # c\n b }";
+        let code = "query {\n  a(x: 1, y: \"z\")\n  b\n}\n";
+        assert_eq!(verify_graphql_equivalence(src, code, &GraphqlConfig::default()), Ok(()));
+    }
+
+    #[test]
+    fn reports_changed_literals() {
+        let err = verify_graphql_equivalence(
+            "{ a(x: 1) }",
+            "{\n  a(x: 2)\n}\n",
+            &GraphqlConfig::default(),
+        )
+        .unwrap_err();
+        assert!(
+            err.message.contains("`1` at 1:8 of the source became `2` at 2:8 of the output"),
+            "{err}"
+        );
+    }
+}
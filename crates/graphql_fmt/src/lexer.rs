@@ -0,0 +1,113 @@
+//! A minimal GraphQL lexer for token-level transformations.
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Name,
+    Number,
+    String,
+    BlockString,
+    /// `...`
+    Spread,
+    Punct,
+    Comment,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+}
+
+/// Splits `src` into tokens. Whitespace, commas and the byte order mark are insignificant and skipped.
+pub(crate) fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = src;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() || c == ',' || c == '\u{feff}' {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let (kind, len) = if c == '#' {
+            (TokenKind::Comment, rest.find(['\n', '\r']).unwrap_or(rest.len()))
+        } else if rest.starts_with("\"\"\"") {
+            (TokenKind::BlockString, block_string_len(rest))
+        } else if c == '"' {
+            (TokenKind::String, string_len(rest))
+        } else if rest.starts_with("...") {
+            (TokenKind::Spread, 3)
+        } else if c == '-' || c.is_ascii_digit() {
+            let len = 1 + rest[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')))
+                .unwrap_or(rest.len() - 1);
+            (TokenKind::Number, len)
+        } else if is_name_char(c) {
+            (TokenKind::Name, rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len()))
+        } else {
+            (TokenKind::Punct, c.len_utf8())
+        };
+
+        tokens.push(Token { kind, text: rest[..len].trim_end() });
+        rest = &rest[len..];
+    }
+
+    tokens
+}
+
+pub(crate) fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn string_len(src: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in src.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return i + 1,
+            _ => escaped = false,
+        }
+    }
+    src.len()
+}
+
+fn block_string_len(src: &str) -> usize {
+    let mut i = 3;
+    while let Some(index) = src[i..].find("\"\"\"") {
+        let end = i + index;
+        if !src[..end].ends_with('\\') {
+            return end + 3;
+        }
+        i = end + 3;
+    }
+    src.len()
+}
+
+/// The `BlockStringValue` algorithm of the GraphQL specification.
+pub(crate) fn block_string_value(raw: &str) -> String {
+    let lines: Vec<_> = raw.split("\r\n").flat_map(|line| line.split(['\n', '\r'])).collect();
+
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| {
+            let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+            (indent < line.len()).then_some(indent)
+        })
+        .min()
+        .unwrap_or(0);
+
+    let lines: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .map(
+            |(index, line)| if index == 0 { line } else { line.get(common_indent..).unwrap_or("") },
+        )
+        .collect();
+
+    let is_blank = |line: &&str| line.trim_start_matches([' ', '\t']).is_empty();
+    let start = lines.iter().position(|line| !is_blank(line)).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !is_blank(line)).map_or(start, |index| index + 1);
+
+    lines[start..end].join("\n")
+}
@@ -1,6 +1,19 @@
+mod canonicalize;
+mod compact;
+#[cfg(feature = "component")]
+mod component;
 pub mod config;
+mod equivalence;
+mod lexer;
+mod range;
 
+pub use common::{
+    CheckResult, FormattedWithCursor, PrintedRange, SourceError, TextEdit, TextRange,
+};
+#[cfg(feature = "wasm-bindgen")]
+use common::{EncodedRange, OffsetConverter, OffsetEncoding, Position};
 use config::GraphqlConfig;
+pub use equivalence::verify_graphql_equivalence;
 #[cfg(feature = "wasm-bindgen")]
 use wasm_bindgen::prelude::*;
 
@@ -9,8 +22,28 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(typescript_type = "Config")]
     pub type Config;
+
+    #[wasm_bindgen(typescript_type = "TextRange | LineColumnRange")]
+    pub type JsTextRange;
 }
 
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(typescript_custom_section)]
+const RANGE: &'static str = r#"
+import type {
+	CheckResult,
+	FormattedWithCursor,
+	LineColumn,
+	LineColumnRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./text.d.ts";
+
+export type * from "./text.d.ts";
+"#;
+
 /// Formats the given GraphQL code with the provided Configuration.
 #[cfg(feature = "wasm-bindgen")]
 #[wasm_bindgen(js_name = format)]
@@ -28,6 +61,183 @@ pub fn format_graphql(
     format_graphql_with_config(src, config)
 }
 
+/// Formats the definitions touched by a range of the given GraphQL code with the provided Configuration.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatRange, unchecked_return_type = "PrintedRange")]
+pub fn format_graphql_range(
+    #[wasm_bindgen(param_description = "The GraphQL code to format")] src: &str,
+    #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let range: EncodedRange =
+        serde_wasm_bindgen::from_value(range.into()).map_err(|e| e.to_string())?;
+    let range = offsets.range_to_utf8(range)?;
+
+    let config: config::GraphqlConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let printed = format_graphql_range_with_config(src, range, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_printed(printed)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given GraphQL code, returning where the cursor ends up in the output.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatWithCursor, unchecked_return_type = "FormattedWithCursor")]
+pub fn format_with_cursor(
+    #[wasm_bindgen(param_description = "The GraphQL code to format")] src: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "number | LineColumn",
+        param_description = "The cursor position, counted as given by `encoding`"
+    )]
+    cursor_offset: JsValue,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the cursor positions are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let config: config::GraphqlConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let cursor_offset: Position =
+        serde_wasm_bindgen::from_value(cursor_offset).map_err(|e| e.to_string())?;
+    let cursor_offset = offsets.to_utf8(cursor_offset)?;
+
+    let formatted = format_graphql_with_cursor(src, cursor_offset, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_cursor(formatted)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given GraphQL code, returning the edits that turn it into the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatEdits, unchecked_return_type = "TextEdit[]")]
+pub fn format_edits(
+    #[wasm_bindgen(param_description = "The GraphQL code to format")] src: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config: config::GraphqlConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let edits = format_graphql_edits_with_config(src, config)?;
+
+    serde_wasm_bindgen::to_value(&edits).map_err(|e| e.to_string())
+}
+
+/// Checks whether the given GraphQL code is already formatted, without returning the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(unchecked_return_type = "CheckResult")]
+pub fn check(
+    #[wasm_bindgen(param_description = "The GraphQL code to check")] src: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config: config::GraphqlConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let result = check_graphql_with_config(src, config)?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
+}
+
 pub fn format_graphql_with_config(src: &str, config: GraphqlConfig) -> Result<String, String> {
-    pretty_graphql::format_text(src, &config.into()).map_err(|e| e.to_string())
+    let compact = config.compact();
+    let strip_comments = config.strip_comments();
+
+    let canonical = match config.canonicalize() {
+        Some(options) => canonicalize::canonicalize(src, &options)?,
+        None => None,
+    };
+    let src = canonical.as_deref().unwrap_or(src);
+
+    let formatted = pretty_graphql::format_text(src, &config.into()).map_err(|e| e.to_string())?;
+
+    if compact {
+        return Ok(compact::compact(&formatted, strip_comments));
+    }
+
+    Ok(formatted)
+}
+
+/// Formats the given code and maps `cursor_offset` to where the cursor ends up in the output.
+pub fn format_graphql_with_cursor(
+    src: &str,
+    cursor_offset: u32,
+    config: GraphqlConfig,
+) -> Result<FormattedWithCursor, String> {
+    FormattedWithCursor::check_cursor(src, cursor_offset)?;
+
+    let code = format_graphql_with_config(src, config)?;
+
+    Ok(FormattedWithCursor::from_diff(src, code, cursor_offset))
+}
+
+/// Formats the given code, returning the minimal edits that turn it into the formatted code.
+pub fn format_graphql_edits_with_config(
+    src: &str,
+    config: GraphqlConfig,
+) -> Result<Vec<TextEdit>, String> {
+    let code = format_graphql_with_config(src, config)?;
+
+    Ok(common::text_edits(src, &code))
+}
+
+/// Checks whether the given code is already formatted, reporting where it first differs otherwise.
+pub fn check_graphql_with_config(src: &str, config: GraphqlConfig) -> Result<CheckResult, String> {
+    let code = format_graphql_with_config(src, config)?;
+
+    Ok(CheckResult::new(src, &code))
+}
+
+/// Formats the top-level definitions touched by `range`.
+///
+/// Definitions can only appear at the top level, so they are formatted on their own.
+pub fn format_graphql_range_with_config(
+    src: &str,
+    range: TextRange,
+    config: GraphqlConfig,
+) -> Result<PrintedRange, String> {
+    if config.compact() || config.canonicalize().is_some() {
+        return Err("compact and canonical output cannot be applied to a range".into());
+    }
+    range.check(src)?;
+
+    let Some((start, end)) = range::enclosing_definitions(src, range) else {
+        // Let the formatter report the syntax error, if any.
+        pretty_graphql::format_text(src, &config.into()).map_err(|e| e.to_string())?;
+        return Ok(PrintedRange { code: String::new(), range: None });
+    };
+
+    let formatted =
+        pretty_graphql::format_text(&src[start..end], &config.into()).map_err(|e| e.to_string())?;
+
+    Ok(PrintedRange {
+        code: formatted.trim_end().to_string(),
+        range: Some(TextRange { start: start as u32, end: end as u32 }),
+    })
 }
@@ -0,0 +1,44 @@
+//! Range formatting for GraphQL documents.
+
+use apollo_parser::{Parser, cst::CstNode};
+use common::TextRange;
+
+use crate::canonicalize::bounds;
+
+/// Finds the top-level definitions touched by `range`.
+///
+/// Returns `None` when no definition is touched or when `src` has syntax errors.
+pub(crate) fn enclosing_definitions(src: &str, range: TextRange) -> Option<(usize, usize)> {
+    let tree = Parser::new(src).parse();
+    if tree.errors().next().is_some() {
+        return None;
+    }
+
+    let mut touched = tree.document().definitions().filter_map(|definition| {
+        let (start, end) = bounds(definition.syntax())?;
+        range.touches(start, end).then_some((start, end))
+    });
+
+    let first = touched.next()?;
+    let last = touched.last().unwrap_or(first);
+    Some((first.0, last.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_touched_definitions() {
+        let src = "# schema\ntype A { a: Int }\n\n\"B\"\ntype B { b: [Int] }\nquery { a }\n";
+        let offset = src.find("b:").unwrap() as u32;
+
+        let (start, end) =
+            enclosing_definitions(src, TextRange { start: offset, end: offset }).unwrap();
+        assert_eq!(&src[start..end], "\"B\"\ntype B { b: [Int] }");
+
+        let (start, end) =
+            enclosing_definitions(src, TextRange { start: 12, end: offset }).unwrap();
+        assert_eq!(&src[start..end], "type A { a: Int }\n\n\"B\"\ntype B { b: [Int] }");
+    }
+}
@@ -1,6 +1,6 @@
 /* @ts-self-types="./json_fmt_web.d.ts" */
 import * as import_bg from "./json_fmt_bg.js";
-const { __wbg_set_wasm, format, ...wasmImport } = import_bg;
+const { __wbg_set_wasm, format, formatRange, formatWithCursor, formatEdits, check, ...wasmImport } = import_bg;
 
 function getImports() {
 	return {
@@ -90,4 +90,4 @@ export default async function initAsync(init_input) {
 	return finalize_init(instance, module);
 }
 
-export { format };
+export { format, formatRange, formatWithCursor, formatEdits, check };
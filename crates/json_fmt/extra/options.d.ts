@@ -3,4 +3,30 @@ import type { LayoutConfig } from "./layout_config.d.ts";
 /**
  * Configuration options for JSON formatter.
  */
-export type Config = LayoutConfig;
+export interface Config extends LayoutConfig {
+	/**
+	 * Reorder object keys. By default keys keep their order.
+	 *
+	 * - `"alphabetical"`: sort the keys of every object alphabetically.
+	 * - `"schema"`: use the conventional order of `package.json`, `tsconfig.json`, `composer.json` or `.eslintrc(.json)`,
+	 *   chosen by the filename passed to `web_fmt`'s `format`. Other files are left untouched.
+	 * - `string[]`: listed keys of the top-level object come first, in the given order.
+	 *
+	 * Comments move together with the key they belong to.
+	 */
+	sortKeys?: "alphabetical" | "schema" | string[];
+
+	/**
+	 * Emit canonical JSON as specified by RFC 8785 (JCS): keys sorted by UTF-16 code units,
+	 * normalized numbers and string escapes, and no whitespace.
+	 * Comments, trailing commas and duplicate keys are rejected. Layout options and `sortKeys` are ignored.
+	 * Defaults to false.
+	 */
+	canonical?: boolean;
+
+	/** Emit minified output instead of pretty printing. Defaults to false. */
+	compact?: boolean;
+
+	/** Remove comments in compact output. Defaults to false. */
+	stripComments?: boolean;
+}
@@ -0,0 +1,269 @@
+//! Canonical JSON output as specified by [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785) (JCS).
+
+use std::fmt::Write;
+
+use crate::tree::{self, Value, decode_string, offset_of, position};
+
+/// Serializes `src` as canonical JSON.
+///
+/// Comments, trailing commas, duplicate keys and numbers that are not representable
+/// as IEEE 754 doubles are rejected with their position in the source.
+pub(crate) fn canonicalize(src: &str) -> Result<String, String> {
+    let document = tree::parse(src)?;
+
+    if let Some(comment) = document.leading.first().or(document.trailing.first()) {
+        return Err(comment_error(src, comment));
+    }
+
+    let mut out = String::with_capacity(src.len());
+    write_value(&mut out, src, &document.value)?;
+    Ok(out)
+}
+
+fn comment_error(src: &str, comment: &str) -> String {
+    format!(
+        "comments are not allowed in canonical JSON at {}",
+        position(src, offset_of(src, comment))
+    )
+}
+
+fn trailing_comma_error(src: &str, offset: usize) -> String {
+    format!("trailing commas are not allowed in canonical JSON at {}", position(src, offset))
+}
+
+fn check_comments<'a>(
+    src: &str,
+    comments: impl IntoIterator<Item = &'a &'a str>,
+) -> Result<(), String> {
+    match comments.into_iter().next() {
+        Some(comment) => Err(comment_error(src, comment)),
+        None => Ok(()),
+    }
+}
+
+fn write_value(out: &mut String, src: &str, value: &Value) -> Result<(), String> {
+    match value {
+        Value::Object(object) => {
+            check_comments(src, &object.dangling)?;
+            if let Some(offset) = object.trailing_comma {
+                return Err(trailing_comma_error(src, offset));
+            }
+
+            let mut members = Vec::with_capacity(object.members.len());
+            for member in &object.members {
+                check_comments(src, member.leading.iter().chain(&member.trailing))?;
+                let name = decode_string(member.key).ok_or_else(|| {
+                    let at = position(src, offset_of(src, member.key));
+                    format!("invalid string escape at {at}")
+                })?;
+                members.push((name, member));
+            }
+
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            if let Some(pair) = members.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                let at = position(src, offset_of(src, pair[1].1.key));
+                return Err(format!("duplicate key `{}` at {at}", pair[1].0));
+            }
+
+            out.push('{');
+            for (index, (name, member)) in members.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_string(out, name);
+                out.push(':');
+                write_value(out, src, &member.value)?;
+            }
+            out.push('}');
+        }
+        Value::Array(array) => {
+            check_comments(src, &array.dangling)?;
+            if let Some(offset) = array.trailing_comma {
+                return Err(trailing_comma_error(src, offset));
+            }
+
+            out.push('[');
+            for (index, element) in array.elements.iter().enumerate() {
+                check_comments(src, element.leading.iter().chain(&element.trailing))?;
+                if index > 0 {
+                    out.push(',');
+                }
+                write_value(out, src, &element.value)?;
+            }
+            out.push(']');
+        }
+        Value::String(raw) => {
+            let value = decode_string(raw).ok_or_else(|| {
+                format!("invalid string escape at {}", position(src, offset_of(src, raw)))
+            })?;
+            write_string(out, &value);
+        }
+        Value::Number(raw) => {
+            let number = parse_number(raw).ok_or_else(|| {
+                format!("invalid number `{raw}` at {}", position(src, offset_of(src, raw)))
+            })?;
+            write_number(out, number);
+        }
+        Value::Literal(raw @ ("true" | "false" | "null")) => out.push_str(raw),
+        Value::Literal(raw) => {
+            return Err(format!("unexpected `{raw}` at {}", position(src, offset_of(src, raw))));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a number following the strict JSON grammar.
+fn parse_number(raw: &str) -> Option<f64> {
+    let bytes = raw.as_bytes();
+    let mut i = usize::from(bytes.first() == Some(&b'-'));
+
+    match bytes.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => {
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+        }
+        _ => return None,
+    }
+
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        let start = i;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if i == start {
+            return None;
+        }
+    }
+
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        let start = i;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if i == start {
+            return None;
+        }
+    }
+
+    if i != bytes.len() {
+        return None;
+    }
+
+    raw.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Writes a number the way ECMAScript's `Number.prototype.toString` does.
+fn write_number(out: &mut String, number: f64) {
+    if number == 0.0 {
+        out.push('0');
+        return;
+    }
+
+    if number < 0.0 {
+        out.push('-');
+    }
+
+    // `{:e}` yields the shortest digits that round-trip, e.g. `1.2345e-7`.
+    let scientific = format!("{:e}", number.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let _ = write!(out, "e{sign}{}", (n - 1).abs());
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < '\u{20}' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::canonicalize;
+
+    #[test]
+    fn serializes_rfc8785_example() {
+        let src = r#"{
+  "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+  "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+  "literals": [null, true, false]
+}"#;
+
+        assert_eq!(
+            canonicalize(src).unwrap(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    #[test]
+    fn sorts_keys_by_utf16_code_units() {
+        let src = "{\"\u{20ac}\": 1, \"\r\": 2, \"\u{fb33}\": 3, \"1\": 4, \"\u{1f600}\": 5, \"\u{80}\": 6, \"\u{f6}\": 7}";
+
+        assert_eq!(
+            canonicalize(src).unwrap(),
+            "{\"\\r\":2,\"1\":4,\"\u{80}\":6,\"\u{f6}\":7,\"\u{20ac}\":1,\"\u{1f600}\":5,\"\u{fb33}\":3}"
+        );
+    }
+
+    #[test]
+    fn rejects_non_canonical_input() {
+        assert_eq!(
+            canonicalize("{\n  \"a\": 1,\n  \"a\": 2\n}").unwrap_err(),
+            "duplicate key `a` at 3:3"
+        );
+        assert_eq!(
+            canonicalize("[1, // one\n2]").unwrap_err(),
+            "comments are not allowed in canonical JSON at 1:5"
+        );
+        assert_eq!(
+            canonicalize("[1, 2,]").unwrap_err(),
+            "trailing commas are not allowed in canonical JSON at 1:6"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! The WebAssembly component, exporting the `formatter` interface of the WIT world in `wit/`.
+//!
+//! The filename picks the key order of well-known files, as for `sortKeys: "schema"`.
+
+use common::component::{parse_config, types};
+
+use crate::{
+    JsonConfig, check_json_with_config, format_json_range_with_config, format_json_with_config,
+};
+
+wit_bindgen::generate!({
+    world: "json-formatter",
+    path: "../../wit",
+    with: { "wasm-fmt:formatter/types": common::component::types },
+});
+
+struct Component;
+
+fn json_config(filename: &str, config: Option<&types::Config>) -> Result<JsonConfig, String> {
+    Ok(parse_config::<JsonConfig>(config)?.with_filename(filename))
+}
+
+impl exports::wasm_fmt::formatter::formatter::Guest for Component {
+    fn format(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<String, String> {
+        format_json_with_config(&src, json_config(&filename, config.as_ref())?)
+    }
+
+    fn format_range(
+        src: String,
+        filename: String,
+        range: types::TextRange,
+        config: Option<types::Config>,
+    ) -> Result<types::PrintedRange, String> {
+        let printed = format_json_range_with_config(
+            &src,
+            range.into(),
+            json_config(&filename, config.as_ref())?,
+        )?;
+
+        Ok(printed.into())
+    }
+
+    fn check(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<types::CheckResult, String> {
+        let result = check_json_with_config(&src, json_config(&filename, config.as_ref())?)?;
+
+        Ok(result.into())
+    }
+}
+
+export!(Component);
@@ -3,21 +3,82 @@ use biome_json_formatter::context::JsonFormatOptions;
 use common::LayoutConfig;
 use serde::Deserialize;
 
+use crate::sort_keys::SortKeys;
+
 #[derive(Deserialize, Default, Clone)]
-#[serde(transparent)]
-pub struct JsonConfig(LayoutConfig);
+pub struct JsonConfig {
+    #[serde(flatten)]
+    layout: LayoutConfig,
+
+    /// Reorder object keys. By default keys keep their order.
+    #[serde(default, alias = "sortKeys")]
+    sort_keys: Option<SortKeys>,
+
+    /// Emit canonical JSON (RFC 8785) instead of pretty printing. Defaults to false.
+    #[serde(default)]
+    canonical: Option<bool>,
+
+    /// Emit minified output instead of pretty printing. Defaults to false.
+    #[serde(default)]
+    compact: Option<bool>,
+
+    /// Remove comments in compact output. Defaults to false.
+    #[serde(default, alias = "stripComments")]
+    strip_comments: Option<bool>,
+
+    #[serde(skip)]
+    filename: Option<String>,
+}
 
 impl From<LayoutConfig> for JsonConfig {
     fn from(config: LayoutConfig) -> Self {
-        Self(config)
+        Self { layout: config, ..Default::default() }
     }
 }
 
 impl JsonConfig {
     pub fn with_line_width(mut self, line_width: u16) -> Self {
-        self.0 = self.0.with_line_width(line_width);
+        self.layout = self.layout.with_line_width(line_width);
+        self
+    }
+
+    /// Set the filename used to pick a well-known key order in `"schema"` mode.
+    #[must_use]
+    pub fn with_filename(mut self, filename: &str) -> Self {
+        self.filename = Some(filename.to_string());
         self
     }
+
+    #[must_use]
+    pub fn fill_empty_layout_with(mut self, layout: &LayoutConfig) -> Self {
+        self.layout = self.layout.fill_empty_with(layout);
+        self
+    }
+
+    #[must_use]
+    pub fn sort_keys(&self) -> Option<&SortKeys> {
+        self.sort_keys.as_ref()
+    }
+
+    #[must_use]
+    pub fn canonical(&self) -> bool {
+        self.canonical.unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn compact(&self) -> bool {
+        self.compact.unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn strip_comments(&self) -> bool {
+        self.strip_comments.unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
 }
 
 impl TryFrom<JsonConfig> for JsonFormatOptions {
@@ -26,21 +87,21 @@ impl TryFrom<JsonConfig> for JsonFormatOptions {
     fn try_from(value: JsonConfig) -> Result<Self, Self::Error> {
         let mut option = JsonFormatOptions::default();
 
-        if let Some(indent_style) = value.0.indent_style() {
+        if let Some(indent_style) = value.layout.indent_style() {
             option = option.with_indent_style(indent_style.as_str().parse()?);
         }
 
-        if let Some(indent_width) = value.0.indent_width() {
+        if let Some(indent_width) = value.layout.indent_width() {
             let indent_width =
                 indent_width.try_into().map_err(|e: IndentWidthFromIntError| e.to_string())?;
             option = option.with_indent_width(indent_width);
         }
 
-        if let Some(line_ending) = value.0.line_ending() {
+        if let Some(line_ending) = value.layout.line_ending() {
             option = option.with_line_ending(line_ending.as_str().parse()?);
         }
 
-        if let Some(line_width) = value.0.line_width() {
+        if let Some(line_width) = value.layout.line_width() {
             let line_width =
                 line_width.try_into().map_err(|e: LineWidthFromIntError| e.to_string())?;
 
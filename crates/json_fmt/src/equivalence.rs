@@ -0,0 +1,86 @@
+//! Checks that formatting keeps the value of JSON documents.
+
+use common::{SourceError, TreeToken, compare_trees, normalize_number};
+
+use crate::tree::{self, Value, decode_string, offset_of};
+
+/// Checks that `code`, the formatted version of `src`, holds the same value.
+///
+/// Comments, trailing commas and the spelling of strings and numbers are ignored,
+/// and so is the order of object keys, which sorting and canonical output change.
+pub fn verify_json_equivalence(src: &str, code: &str) -> Result<(), SourceError> {
+    let before = tree::parse(src)?;
+    let after =
+        tree::parse(code).map_err(|e| format!("the formatted JSON has syntax errors: {e}"))?;
+
+    compare_trees(
+        "JSON",
+        src,
+        &value_tokens(src, &before.value),
+        code,
+        &value_tokens(code, &after.value),
+    )
+}
+
+/// Flattens `value` to normalized tokens, with object members sorted by key.
+fn value_tokens(src: &str, value: &Value) -> Vec<TreeToken> {
+    let mut tokens = vec![];
+    push_value(&mut tokens, src, value);
+    tokens
+}
+
+fn push_value(tokens: &mut Vec<TreeToken>, src: &str, value: &Value) {
+    // Brackets have no offset of their own, so they borrow the one of the previous token.
+    let at = tokens.last().map_or(0, |token: &TreeToken| token.offset);
+
+    match value {
+        Value::Object(object) => {
+            let mut members: Vec<_> = object.members.iter().collect();
+            members.sort_by_cached_key(|member| member.name());
+
+            tokens.push(TreeToken::new("{", at));
+            for member in members {
+                let key = format!("{:?}:", member.name());
+                tokens.push(TreeToken::new(key, offset_of(src, member.key) as u32));
+                push_value(tokens, src, &member.value);
+            }
+            tokens.push(TreeToken::new("}", at));
+        }
+        Value::Array(array) => {
+            tokens.push(TreeToken::new("[", at));
+            for element in &array.elements {
+                push_value(tokens, src, &element.value);
+            }
+            tokens.push(TreeToken::new("]", at));
+        }
+        Value::String(raw) => {
+            let text =
+                decode_string(raw).map_or_else(|| raw.to_string(), |text| format!("{text:?}"));
+            tokens.push(TreeToken::new(text, offset_of(src, raw) as u32));
+        }
+        Value::Number(raw) => {
+            tokens.push(TreeToken::new(normalize_number(raw), offset_of(src, raw) as u32));
+        }
+        Value::Literal(raw) => tokens.push(TreeToken::new(*raw, offset_of(src, raw) as u32)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_unchanged_values() {
+        let src = r#"{"b": [1.0, "A"], "a": null}"#;
+        assert_eq!(verify_json_equivalence(src, "{ \"a\": null, \"b\": [1, \"A\"] }\n"), Ok(()));
+    }
+
+    #[test]
+    fn reports_changed_literals() {
+        let err = verify_json_equivalence(r#"{"a": "x"}"#, "{ \"a\": \"y\" }\n").unwrap_err();
+        assert!(
+            err.message.contains(r#"`"x"` at 1:7 of the source became `"y"` at 1:8 of the output"#),
+            "{err}"
+        );
+    }
+}
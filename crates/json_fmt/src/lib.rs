@@ -1,10 +1,24 @@
+mod canonical;
+#[cfg(feature = "component")]
+mod component;
 mod config;
+mod equivalence;
+mod sort_keys;
+mod tree;
 
 use biome_formatter::Printed;
-use biome_json_formatter::format_node;
+use biome_json_formatter::{format_node, format_range as biome_format_range};
 use biome_json_parser::{JsonParserOptions, parse_json};
+use biome_json_syntax::{TextRange as BiomeTextRange, TextSize};
 
-use config::JsonConfig;
+pub use common::{
+    CheckResult, FormattedWithCursor, PrintedRange, SourceError, TextEdit, TextRange,
+};
+#[cfg(feature = "wasm-bindgen")]
+use common::{EncodedRange, OffsetConverter, OffsetEncoding, Position};
+pub use config::JsonConfig;
+pub use equivalence::verify_json_equivalence;
+pub use sort_keys::{SortKeys, SortKeysMode};
 #[cfg(feature = "wasm-bindgen")]
 use wasm_bindgen::prelude::*;
 
@@ -13,6 +27,37 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(typescript_type = "Config")]
     pub type Config;
+
+    #[wasm_bindgen(typescript_type = "TextRange | LineColumnRange")]
+    pub type JsTextRange;
+}
+
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(typescript_custom_section)]
+const RANGE: &'static str = r#"
+import type {
+	CheckResult,
+	FormattedWithCursor,
+	LineColumn,
+	LineColumnRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./text.d.ts";
+
+export type * from "./text.d.ts";
+"#;
+
+fn to_biome_range(range: TextRange) -> BiomeTextRange {
+    BiomeTextRange::new(TextSize::from(range.start), TextSize::from(range.end))
+}
+
+fn printed_range(printed: Printed) -> PrintedRange {
+    let range = printed
+        .range()
+        .map(|range| TextRange { start: range.start().into(), end: range.end().into() });
+    PrintedRange { code: printed.into_code(), range }
 }
 
 /// Formats the given JSON code with the provided Configuration.
@@ -31,15 +76,267 @@ pub fn format(
     format_json_with_config(src, config)
 }
 
+/// Formats a range of the given JSON code with the provided Configuration.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatRange, unchecked_return_type = "PrintedRange")]
+pub fn format_range(
+    #[wasm_bindgen(param_description = "The JSON code to format")] src: &str,
+    #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let range: EncodedRange =
+        serde_wasm_bindgen::from_value(range.into()).map_err(|e| e.to_string())?;
+    let range = offsets.range_to_utf8(range)?;
+
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let printed = format_json_range_with_config(src, range, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_printed(printed)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given JSON code, returning where the cursor ends up in the output.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatWithCursor, unchecked_return_type = "FormattedWithCursor")]
+pub fn format_with_cursor(
+    #[wasm_bindgen(param_description = "The JSON code to format")] src: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "number | LineColumn",
+        param_description = "The cursor position, counted as given by `encoding`"
+    )]
+    cursor_offset: JsValue,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the cursor positions are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let cursor_offset: Position =
+        serde_wasm_bindgen::from_value(cursor_offset).map_err(|e| e.to_string())?;
+    let cursor_offset = offsets.to_utf8(cursor_offset)?;
+
+    let formatted = format_json_with_cursor(src, cursor_offset, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_cursor(formatted)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given JSON code, returning the edits that turn it into the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatEdits, unchecked_return_type = "TextEdit[]")]
+pub fn format_edits(
+    #[wasm_bindgen(param_description = "The JSON code to format")] src: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let edits = format_json_edits_with_config(src, config)?;
+
+    serde_wasm_bindgen::to_value(&edits).map_err(|e| e.to_string())
+}
+
+/// Checks whether the given JSON code is already formatted, without returning the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(unchecked_return_type = "CheckResult")]
+pub fn check(
+    #[wasm_bindgen(param_description = "The JSON code to check")] src: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let result = check_json_with_config(src, config)?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
+}
+
 pub fn format_json_with_config(src: &str, config: JsonConfig) -> Result<String, String> {
+    if config.canonical() {
+        return canonical::canonicalize(src);
+    }
+
+    if config.compact() {
+        let sorted = sorted_keys(src, &config);
+        let document = tree::parse(sorted.as_deref().unwrap_or(src))?;
+        return Ok(tree::print_compact(&document, config.strip_comments()));
+    }
+
+    print_json(src, config).map(|(printed, _)| printed.into_code())
+}
+
+/// Formats the given code and maps `cursor_offset` to where the cursor ends up in the output.
+///
+/// The cursor is mapped through the source markers of the printer: it keeps its distance to
+/// the start of the token it is in. When keys were sorted, or with canonical or compact output,
+/// the cursor is mapped by diffing.
+pub fn format_json_with_cursor(
+    src: &str,
+    cursor_offset: u32,
+    config: JsonConfig,
+) -> Result<FormattedWithCursor, String> {
+    FormattedWithCursor::check_cursor(src, cursor_offset)?;
+
+    if config.canonical() || config.compact() {
+        let code = format_json_with_config(src, config)?;
+        return Ok(FormattedWithCursor::from_diff(src, code, cursor_offset));
+    }
+
+    let (printed, sorted) = print_json(src, config)?;
+
+    if sorted {
+        return Ok(FormattedWithCursor::from_diff(src, printed.into_code(), cursor_offset));
+    }
+
+    let markers: Vec<_> = printed
+        .sourcemap()
+        .iter()
+        .map(|marker| (u32::from(marker.source) as usize, u32::from(marker.dest) as usize))
+        .collect();
+    let cursor_offset =
+        common::map_offset_with_markers(printed.as_code(), &markers, cursor_offset as usize);
+
+    Ok(FormattedWithCursor { code: printed.into_code(), cursor_offset: cursor_offset as u32 })
+}
+
+/// Formats the whole code with the Biome printer, returning whether keys were sorted before formatting.
+fn print_json(src: &str, config: JsonConfig) -> Result<(Printed, bool), String> {
+    let sorted = sorted_keys(src, &config);
+
     let options = JsonParserOptions::default().with_allow_comments().with_allow_trailing_commas();
-    let parse = parse_json(src, options);
+    let parse = parse_json(sorted.as_deref().unwrap_or(src), options);
 
     let options = config.try_into()?;
 
-    format_node(options, &parse.syntax())
+    let printed = format_node(options, &parse.syntax())
         .map_err(|e| e.to_string())?
         .print()
-        .map(Printed::into_code)
+        .map_err(|e| e.to_string())?;
+
+    Ok((printed, sorted.is_some()))
+}
+
+fn sorted_keys(src: &str, config: &JsonConfig) -> Option<String> {
+    config.sort_keys().and_then(|sort_keys| sort_keys::sort_keys(src, sort_keys, config.filename()))
+}
+
+/// Formats the given code, returning the minimal edits that turn it into the formatted code.
+pub fn format_json_edits_with_config(
+    src: &str,
+    config: JsonConfig,
+) -> Result<Vec<TextEdit>, String> {
+    let code = format_json_with_config(src, config)?;
+
+    Ok(common::text_edits(src, &code))
+}
+
+/// Checks whether the given code is already formatted, reporting where it first differs otherwise.
+pub fn check_json_with_config(src: &str, config: JsonConfig) -> Result<CheckResult, String> {
+    let code = format_json_with_config(src, config)?;
+
+    Ok(CheckResult::new(src, &code))
+}
+
+/// Formats the smallest node list enclosing `range`.
+///
+/// Only layout options apply: `sortKeys`, `canonical` and `compact` rewrite the whole document.
+pub fn format_json_range_with_config(
+    src: &str,
+    range: TextRange,
+    config: JsonConfig,
+) -> Result<PrintedRange, String> {
+    if config.canonical() || config.compact() {
+        return Err("canonical and compact output cannot be applied to a range".into());
+    }
+
+    range.check(src)?;
+
+    let options = JsonParserOptions::default().with_allow_comments().with_allow_trailing_commas();
+    let parse = parse_json(src, options);
+
+    let options = config.try_into()?;
+
+    biome_format_range(options, &parse.syntax(), to_biome_range(range))
+        .map(printed_range)
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_range(src: &str, start: usize, end: usize) -> Result<String, String> {
+        let range = TextRange { start: start as u32, end: end as u32 };
+        let printed = format_json_range_with_config(src, range, JsonConfig::default())?;
+        let range = printed.range.expect("a formatted range");
+
+        Ok(format!(
+            "{}{}{}",
+            &src[..range.start as usize],
+            printed.code,
+            &src[range.end as usize..]
+        ))
+    }
+
+    #[test]
+    fn formats_the_enclosing_node() {
+        let src = "{\n\t\"a\": 1,\n\t\"b\":   [1,2]\n}\n";
+        let start = src.find('[').unwrap();
+
+        assert_eq!(
+            format_range(src, start, start + 5).unwrap(),
+            format_json_with_config(src, JsonConfig::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn maps_the_cursor_through_source_markers() {
+        let src = "{\"a\":1,\n\"bc\":   [1,2]}";
+        let offset = src.find("c\"").unwrap();
+
+        let formatted = format_json_with_cursor(src, offset as u32, JsonConfig::default()).unwrap();
+        assert!(formatted.code[formatted.cursor_offset as usize..].starts_with("c\": [1, 2]"));
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        let src = "{\"\u{e9}\": 1}";
+
+        assert_eq!(format_range(src, 3, 3).unwrap_err(), "range 3..3 is out of bounds");
+        assert_eq!(format_range(src, 5, 4).unwrap_err(), "range 5..4 is out of bounds");
+        assert_eq!(format_range(src, 0, 20).unwrap_err(), "range 0..20 is out of bounds");
+    }
+
+    #[test]
+    fn rejects_whole_document_options() {
+        let config: JsonConfig = serde_json::from_str(r#"{"compact": true}"#).unwrap();
+        let range = TextRange { start: 0, end: 1 };
+
+        assert!(format_json_range_with_config("[1]", range, config).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::tree::{self, Member, Object, Value};
+
+/// How object keys are ordered.
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum SortKeys {
+    Mode(SortKeysMode),
+    /// Listed keys of the top-level object come first, in the given order.
+    Custom(Vec<String>),
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKeysMode {
+    /// Sort keys of every object alphabetically.
+    Alphabetical,
+    /// Use the conventional key order of a well-known file, chosen by filename.
+    Schema,
+}
+
+struct Schema {
+    /// Key order of the top-level object. Unknown keys keep their order after the known ones.
+    order: &'static [&'static str],
+    /// Top-level keys whose object values are sorted alphabetically.
+    sorted: &'static [&'static str],
+}
+
+/// Reorders object keys of `src`.
+///
+/// Returns `None` when nothing moved, or when the source cannot be parsed,
+/// in which case the formatter reports the syntax error.
+pub(crate) fn sort_keys(src: &str, sort_keys: &SortKeys, filename: Option<&str>) -> Option<String> {
+    let mut document = tree::parse(src).ok()?;
+    let Value::Object(root) = &mut document.value else {
+        return None;
+    };
+
+    let changed = match sort_keys {
+        SortKeys::Mode(SortKeysMode::Alphabetical) => sort_alphabetical(root),
+        SortKeys::Custom(order) => {
+            sort_by_order(root, &order.iter().map(String::as_str).collect::<Vec<_>>())
+        }
+        SortKeys::Mode(SortKeysMode::Schema) => {
+            let schema = filename.and_then(schema_for)?;
+            let mut changed = sort_by_order(root, schema.order);
+            for member in &mut root.members {
+                if schema.sorted.contains(&member.name().as_str())
+                    && let Value::Object(object) = &mut member.value
+                {
+                    changed |= sort_members(object, |a, b| a.name().cmp(&b.name()));
+                }
+            }
+            changed
+        }
+    };
+
+    changed.then(|| tree::print(&document))
+}
+
+fn sort_alphabetical(object: &mut Object) -> bool {
+    let mut changed = sort_members(object, |a, b| a.name().cmp(&b.name()));
+
+    for member in &mut object.members {
+        changed |= sort_nested(&mut member.value);
+    }
+
+    changed
+}
+
+fn sort_nested(value: &mut Value) -> bool {
+    match value {
+        Value::Object(object) => sort_alphabetical(object),
+        Value::Array(array) => {
+            array.elements.iter_mut().fold(false, |changed, e| sort_nested(&mut e.value) | changed)
+        }
+        _ => false,
+    }
+}
+
+fn sort_by_order(object: &mut Object, order: &[&str]) -> bool {
+    let rank = |member: &Member| {
+        let name = member.name();
+        order.iter().position(|key| *key == name).unwrap_or(order.len())
+    };
+
+    sort_members(object, |a, b| rank(a).cmp(&rank(b)))
+}
+
+/// Stable-sorts the members, returns whether any member moved.
+fn sort_members<'a>(
+    object: &mut Object<'a>,
+    compare: impl Fn(&Member<'a>, &Member<'a>) -> std::cmp::Ordering,
+) -> bool {
+    let before: Vec<_> = object.members.iter().map(|m| m.key.as_ptr()).collect();
+    object.members.sort_by(|a, b| compare(a, b));
+    object.members.iter().map(|m| m.key.as_ptr()).ne(before)
+}
+
+fn schema_for(filename: &str) -> Option<&'static Schema> {
+    let name = Path::new(filename).file_name()?.to_str()?;
+
+    match name {
+        "package.json" => Some(&PACKAGE_JSON),
+        "composer.json" => Some(&COMPOSER_JSON),
+        ".eslintrc" | ".eslintrc.json" => Some(&ESLINTRC_JSON),
+        "tsconfig.json" | "jsconfig.json" => Some(&TSCONFIG_JSON),
+        _ if name.starts_with("tsconfig.") && name.ends_with(".json") => Some(&TSCONFIG_JSON),
+        _ => None,
+    }
+}
+
+/// Key order of sort-package-json.
+static PACKAGE_JSON: Schema = Schema {
+    order: &[
+        "$schema",
+        "name",
+        "displayName",
+        "version",
+        "private",
+        "description",
+        "categories",
+        "keywords",
+        "homepage",
+        "bugs",
+        "repository",
+        "funding",
+        "license",
+        "qna",
+        "author",
+        "maintainers",
+        "contributors",
+        "publisher",
+        "sideEffects",
+        "type",
+        "imports",
+        "exports",
+        "main",
+        "svelte",
+        "umd:main",
+        "jsdelivr",
+        "unpkg",
+        "module",
+        "source",
+        "jsnext:main",
+        "browser",
+        "react-native",
+        "types",
+        "typesVersions",
+        "typings",
+        "style",
+        "example",
+        "examplestyle",
+        "assets",
+        "bin",
+        "man",
+        "directories",
+        "files",
+        "workspaces",
+        "binary",
+        "scripts",
+        "betterScripts",
+        "contributes",
+        "activationEvents",
+        "husky",
+        "simple-git-hooks",
+        "pre-commit",
+        "commitlint",
+        "lint-staged",
+        "nano-staged",
+        "config",
+        "nodemonConfig",
+        "browserify",
+        "babel",
+        "browserslist",
+        "xo",
+        "prettier",
+        "eslintConfig",
+        "eslintIgnore",
+        "npmpackagejsonlint",
+        "release",
+        "remarkConfig",
+        "stylelint",
+        "ava",
+        "jest",
+        "jest-junit",
+        "jest-stare",
+        "mocha",
+        "nyc",
+        "c8",
+        "tap",
+        "oclif",
+        "resolutions",
+        "dependencies",
+        "devDependencies",
+        "dependenciesMeta",
+        "peerDependencies",
+        "peerDependenciesMeta",
+        "optionalDependencies",
+        "bundledDependencies",
+        "bundleDependencies",
+        "extensionPack",
+        "extensionDependencies",
+        "flat",
+        "packageManager",
+        "engines",
+        "engineStrict",
+        "volta",
+        "languageName",
+        "os",
+        "cpu",
+        "preferGlobal",
+        "publishConfig",
+        "icon",
+        "badges",
+        "galleryBanner",
+        "preview",
+        "markdown",
+        "pnpm",
+    ],
+    sorted: &[
+        "dependencies",
+        "devDependencies",
+        "dependenciesMeta",
+        "peerDependencies",
+        "peerDependenciesMeta",
+        "optionalDependencies",
+        "resolutions",
+        "engines",
+    ],
+};
+
+static TSCONFIG_JSON: Schema = Schema {
+    order: &[
+        "$schema",
+        "extends",
+        "compilerOptions",
+        "include",
+        "exclude",
+        "files",
+        "references",
+        "watchOptions",
+        "typeAcquisition",
+    ],
+    sorted: &[],
+};
+
+static COMPOSER_JSON: Schema = Schema {
+    order: &[
+        "$schema",
+        "name",
+        "type",
+        "description",
+        "keywords",
+        "homepage",
+        "readme",
+        "version",
+        "time",
+        "license",
+        "authors",
+        "support",
+        "funding",
+        "require",
+        "require-dev",
+        "conflict",
+        "replace",
+        "provide",
+        "suggest",
+        "autoload",
+        "autoload-dev",
+        "include-path",
+        "target-dir",
+        "minimum-stability",
+        "prefer-stable",
+        "repositories",
+        "config",
+        "scripts",
+        "scripts-descriptions",
+        "extra",
+        "bin",
+        "archive",
+        "abandoned",
+        "non-feature-branches",
+    ],
+    sorted: &["require", "require-dev", "conflict", "replace", "provide", "suggest"],
+};
+
+static ESLINTRC_JSON: Schema = Schema {
+    order: &[
+        "$schema",
+        "root",
+        "env",
+        "globals",
+        "parser",
+        "parserOptions",
+        "plugins",
+        "extends",
+        "settings",
+        "ignorePatterns",
+        "rules",
+        "overrides",
+    ],
+    sorted: &["env", "globals"],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_json_keys_follow_schema_and_keep_comments() {
+        let src = r#"{
+  "dependencies": { "b": "1", "a": "1" },
+  // the package name
+  "name": "x",
+  "custom": true
+}"#;
+
+        let sort = SortKeys::Mode(SortKeysMode::Schema);
+        let sorted = sort_keys(src, &sort, Some("pkg/package.json")).unwrap();
+
+        assert_eq!(
+            sorted,
+            r#"{
+// the package name
+"name": "x",
+"dependencies": {"a": "1", "b": "1"},
+"custom": true
+}
+"#
+        );
+    }
+
+    #[test]
+    fn schema_mode_ignores_unknown_files() {
+        let sort = SortKeys::Mode(SortKeysMode::Schema);
+        assert!(sort_keys(r#"{"b": 1, "a": 2}"#, &sort, Some("data.json")).is_none());
+    }
+}
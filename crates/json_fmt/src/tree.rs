@@ -0,0 +1,574 @@
+//! A small JSONC syntax tree for transformations that move entries around.
+//!
+//! Comments are attached to the entry they precede, or to the entry whose line they trail,
+//! so that they travel with it when entries are reordered.
+
+pub(crate) struct Document<'a> {
+    pub leading: Vec<&'a str>,
+    pub value: Value<'a>,
+    pub trailing: Vec<&'a str>,
+}
+
+pub(crate) enum Value<'a> {
+    Object(Object<'a>),
+    Array(Array<'a>),
+    /// A string literal, including its quotes.
+    String(&'a str),
+    Number(&'a str),
+    /// `true`, `false` or `null`.
+    Literal(&'a str),
+}
+
+pub(crate) struct Object<'a> {
+    pub members: Vec<Member<'a>>,
+    /// Comments after the last member.
+    pub dangling: Vec<&'a str>,
+    /// Whether the source had a line break after `{`.
+    pub multiline: bool,
+    /// Offset of a trailing comma after the last member.
+    pub trailing_comma: Option<usize>,
+}
+
+pub(crate) struct Member<'a> {
+    pub leading: Vec<&'a str>,
+    /// The key string literal, including its quotes.
+    pub key: &'a str,
+    pub value: Value<'a>,
+    pub trailing: Vec<&'a str>,
+}
+
+pub(crate) struct Array<'a> {
+    pub elements: Vec<Element<'a>>,
+    /// Comments after the last element.
+    pub dangling: Vec<&'a str>,
+    /// Whether the source had a line break after `[`.
+    pub multiline: bool,
+    /// Offset of a trailing comma after the last element.
+    pub trailing_comma: Option<usize>,
+}
+
+pub(crate) struct Element<'a> {
+    pub leading: Vec<&'a str>,
+    pub value: Value<'a>,
+    pub trailing: Vec<&'a str>,
+}
+
+impl Member<'_> {
+    /// The decoded key, falling back to the raw text for invalid escapes.
+    pub fn name(&self) -> String {
+        decode_string(self.key).unwrap_or_else(|| self.key.trim_matches('"').to_string())
+    }
+}
+
+pub(crate) fn parse(src: &str) -> Result<Document<'_>, String> {
+    let mut parser = Parser { src, pos: 0 };
+
+    let leading = parser.comments();
+    let value = parser.value()?;
+    let trailing = parser.comments();
+
+    if parser.pos < src.len() {
+        return Err(parser.error("expected end of input"));
+    }
+
+    Ok(Document { leading, value, trailing })
+}
+
+/// Formats the `line:column` of a byte offset, both 1-based.
+pub(crate) fn position(src: &str, offset: usize) -> String {
+    let before = &src[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[before.rfind('\n').map_or(0, |i| i + 1)..].chars().count() + 1;
+    format!("{line}:{column}")
+}
+
+/// Byte offset of a slice borrowed from `src`.
+pub(crate) fn offset_of(src: &str, slice: &str) -> usize {
+    slice.as_ptr() as usize - src.as_ptr() as usize
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{message} at {}", position(self.src, self.pos))
+    }
+
+    /// Skips whitespace and returns the comments found on the way.
+    fn comments(&mut self) -> Vec<&'a str> {
+        let mut comments = vec![];
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            match self.comment() {
+                Some(comment) => comments.push(comment),
+                None => return comments,
+            }
+        }
+    }
+
+    /// Returns the comments before the next line break.
+    fn trailing_comments(&mut self) -> Vec<&'a str> {
+        let mut comments = vec![];
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start_matches([' ', '\t']);
+            self.pos += rest.len() - trimmed.len();
+
+            match self.comment() {
+                Some(comment) if comment.starts_with("//") => {
+                    comments.push(comment);
+                    return comments;
+                }
+                Some(comment) => comments.push(comment),
+                None => return comments,
+            }
+        }
+    }
+
+    /// Returns the comments trailing an entry and the offset of the `,` separator following it.
+    /// Comments on later lines only belong to the entry when a separator follows them.
+    fn separator(&mut self) -> (Vec<&'a str>, Option<usize>) {
+        let mut trailing = self.trailing_comments();
+        let checkpoint = self.pos;
+        let more = self.comments();
+
+        if self.peek() != Some(b',') {
+            self.pos = checkpoint;
+            return (trailing, None);
+        }
+
+        let comma = self.pos;
+        self.pos += 1;
+        trailing.extend(more);
+        trailing.extend(self.trailing_comments());
+        (trailing, Some(comma))
+    }
+
+    fn comment(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let len = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            body.find("*/").map_or(rest.len(), |i| i + 4)
+        } else {
+            return None;
+        };
+        self.pos += len;
+        Some(rest[..len].trim_end())
+    }
+
+    fn has_line_break(&self) -> bool {
+        let rest = self.rest();
+        rest[..rest.len() - rest.trim_start().len()].contains('\n')
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", byte as char)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value<'a>, String> {
+        match self.peek() {
+            Some(b'{') => self.object().map(Value::Object),
+            Some(b'[') => self.array().map(Value::Array),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-' | b'0'..=b'9') => self.number().map(Value::Number),
+            Some(b'a'..=b'z') => self.literal().map(Value::Literal),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    /// The length of the identifier-like word at the current position.
+    fn word_len(&self) -> usize {
+        let rest = self.rest();
+        rest.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')))
+            .unwrap_or(rest.len())
+    }
+
+    fn literal(&mut self) -> Result<&'a str, String> {
+        let word = &self.rest()[..self.word_len()];
+        if !matches!(word, "true" | "false" | "null") {
+            return Err(self.error(&format!("unexpected `{word}`")));
+        }
+        self.pos += word.len();
+        Ok(word)
+    }
+
+    /// A number of the JSON grammar: `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`.
+    fn number(&mut self) -> Result<&'a str, String> {
+        let word = &self.rest()[..self.word_len()];
+        let bytes = word.as_bytes();
+        let digits =
+            |from: usize| from + bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+        let mut len = usize::from(bytes.first() == Some(&b'-'));
+        let integer = digits(len);
+        let valid_integer = integer > len && (bytes[len] != b'0' || integer == len + 1);
+        len = integer;
+
+        let mut valid = valid_integer;
+        if valid && bytes.get(len) == Some(&b'.') {
+            let fraction = digits(len + 1);
+            valid = fraction > len + 1;
+            len = fraction;
+        }
+        if valid && matches!(bytes.get(len), Some(b'e' | b'E')) {
+            let sign = len + 1 + usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+            let exponent = digits(sign);
+            valid = exponent > sign;
+            len = exponent;
+        }
+
+        if !valid || len != word.len() {
+            return Err(self.error(&format!("invalid number `{word}`")));
+        }
+        self.pos += len;
+        Ok(word)
+    }
+
+    fn string(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let mut escaped = false;
+        for (i, c) in rest.char_indices().skip(1) {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => {
+                    let literal = &rest[..=i];
+                    if decode_string(literal).is_none() {
+                        return Err(self.error("invalid escape in string"));
+                    }
+                    self.pos += i + 1;
+                    return Ok(literal);
+                }
+                '\n' => break,
+                _ => escaped = false,
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn object(&mut self) -> Result<Object<'a>, String> {
+        self.expect(b'{')?;
+        let multiline = self.has_line_break();
+        let mut members = vec![];
+        let mut comma = None;
+
+        loop {
+            let mut leading = self.comments();
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(Object { members, dangling: leading, multiline, trailing_comma: comma });
+            }
+
+            let key = self.string()?;
+            leading.extend(self.comments());
+            self.expect(b':')?;
+            leading.extend(self.comments());
+            let value = self.value()?;
+
+            let (trailing, separator) = self.separator();
+            members.push(Member { leading, key, value, trailing });
+
+            if separator.is_none() {
+                let dangling = self.comments();
+                self.expect(b'}')?;
+                return Ok(Object { members, dangling, multiline, trailing_comma: None });
+            }
+            comma = separator;
+        }
+    }
+
+    fn array(&mut self) -> Result<Array<'a>, String> {
+        self.expect(b'[')?;
+        let multiline = self.has_line_break();
+        let mut elements = vec![];
+        let mut comma = None;
+
+        loop {
+            let leading = self.comments();
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Array { elements, dangling: leading, multiline, trailing_comma: comma });
+            }
+
+            let value = self.value()?;
+
+            let (trailing, separator) = self.separator();
+            elements.push(Element { leading, value, trailing });
+
+            if separator.is_none() {
+                let dangling = self.comments();
+                self.expect(b']')?;
+                return Ok(Array { elements, dangling, multiline, trailing_comma: None });
+            }
+            comma = separator;
+        }
+    }
+}
+
+/// Decodes a JSON string literal (including quotes) into its value.
+pub(crate) fn decode_string(raw: &str) -> Option<String> {
+    let inner = raw.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            '"' => result.push('"'),
+            '\\' => result.push('\\'),
+            '/' => result.push('/'),
+            'b' => result.push('\u{8}'),
+            'f' => result.push('\u{c}'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            't' => result.push('\t'),
+            'u' => {
+                let high = read_hex4(&mut chars)?;
+                let code = if (0xD800..0xDC00).contains(&high) {
+                    if chars.next()? != '\\' || chars.next()? != 'u' {
+                        return None;
+                    }
+                    let low = read_hex4(&mut chars)?;
+                    0x10000 + ((high - 0xD800) << 10) + (low.checked_sub(0xDC00)?)
+                } else {
+                    high
+                };
+                result.push(char::from_u32(code)?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(result)
+}
+
+fn read_hex4(chars: &mut std::str::Chars) -> Option<u32> {
+    let hex: String = chars.take(4).collect();
+    if hex.len() != 4 {
+        return None;
+    }
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+/// Prints the tree as JSONC, one entry per line for expanded containers.
+///
+/// The output is meant to be fed back into the formatter, which takes care of indentation.
+pub(crate) fn print(document: &Document) -> String {
+    let mut out = String::new();
+    print_comments(&mut out, &document.leading);
+    print_value(&mut out, &document.value);
+    out.push('\n');
+    print_comments(&mut out, &document.trailing);
+    out
+}
+
+fn print_comments(out: &mut String, comments: &[&str]) {
+    for comment in comments {
+        out.push_str(comment);
+        out.push('\n');
+    }
+}
+
+fn print_trailing(out: &mut String, comments: &[&str]) {
+    for comment in comments {
+        if out.ends_with('\n') {
+            out.push_str(comment);
+        } else {
+            out.push(' ');
+            out.push_str(comment);
+        }
+        if comment.starts_with("//") {
+            out.push('\n');
+        }
+    }
+}
+
+fn print_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Object(object) => {
+            let expanded = object.multiline
+                || !object.dangling.is_empty()
+                || object.members.iter().any(|m| !m.leading.is_empty() || !m.trailing.is_empty());
+
+            out.push('{');
+            for (index, member) in object.members.iter().enumerate() {
+                if expanded {
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    print_comments(out, &member.leading);
+                } else if index > 0 {
+                    out.push(' ');
+                }
+                out.push_str(member.key);
+                out.push_str(": ");
+                print_value(out, &member.value);
+                if index + 1 < object.members.len() {
+                    out.push(',');
+                }
+                print_trailing(out, &member.trailing);
+            }
+            if expanded {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                print_comments(out, &object.dangling);
+            }
+            out.push('}');
+        }
+        Value::Array(array) => {
+            let expanded = array.multiline
+                || !array.dangling.is_empty()
+                || array.elements.iter().any(|e| !e.leading.is_empty() || !e.trailing.is_empty());
+
+            out.push('[');
+            for (index, element) in array.elements.iter().enumerate() {
+                if expanded {
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    print_comments(out, &element.leading);
+                } else if index > 0 {
+                    out.push(' ');
+                }
+                print_value(out, &element.value);
+                if index + 1 < array.elements.len() {
+                    out.push(',');
+                }
+                print_trailing(out, &element.trailing);
+            }
+            if expanded {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                print_comments(out, &array.dangling);
+            }
+            out.push(']');
+        }
+        Value::String(text) | Value::Number(text) | Value::Literal(text) => out.push_str(text),
+    }
+}
+
+/// Prints the tree without insignificant whitespace.
+///
+/// Line comments are turned into block comments so that they fit on a single line.
+pub(crate) fn print_compact(document: &Document, strip_comments: bool) -> String {
+    let mut out = String::new();
+    let mut comments = |out: &mut String, comments: &[&str]| {
+        if !strip_comments {
+            for comment in comments {
+                match comment.strip_prefix("//") {
+                    Some(text) if !text.contains("*/") => {
+                        out.push_str("/*");
+                        out.push_str(text);
+                        out.push_str(" */");
+                    }
+                    _ => out.push_str(comment),
+                }
+                if comment.starts_with("//") && comment.contains("*/") {
+                    out.push('\n');
+                }
+            }
+        }
+    };
+
+    comments(&mut out, &document.leading);
+    print_compact_value(&mut out, &document.value, &mut comments);
+    comments(&mut out, &document.trailing);
+    out
+}
+
+fn print_compact_value(
+    out: &mut String,
+    value: &Value,
+    comments: &mut impl FnMut(&mut String, &[&str]),
+) {
+    match value {
+        Value::Object(object) => {
+            out.push('{');
+            for (index, member) in object.members.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                comments(out, &member.leading);
+                out.push_str(member.key);
+                out.push(':');
+                print_compact_value(out, &member.value, comments);
+                comments(out, &member.trailing);
+            }
+            comments(out, &object.dangling);
+            out.push('}');
+        }
+        Value::Array(array) => {
+            out.push('[');
+            for (index, element) in array.elements.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                comments(out, &element.leading);
+                print_compact_value(out, &element.value, comments);
+                comments(out, &element.trailing);
+            }
+            comments(out, &array.dangling);
+            out.push(']');
+        }
+        Value::String(text) | Value::Number(text) | Value::Literal(text) => out.push_str(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, print_compact};
+
+    fn compact(src: &str) -> Result<String, String> {
+        parse(src).map(|document| print_compact(&document, false))
+    }
+
+    #[test]
+    fn compacts_valid_values() {
+        assert_eq!(
+            compact("{\"a\": [0, -1.5e+3, 2E-2, true, null] , \"b\\n\": false}").unwrap(),
+            "{\"a\":[0,-1.5e+3,2E-2,true,null],\"b\\n\":false}"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_literals() {
+        assert_eq!(compact("{\"a\": foo}").unwrap_err(), "unexpected `foo` at 1:7");
+        assert_eq!(compact("[True]").unwrap_err(), "expected a value at 1:2");
+        assert_eq!(compact("[nulls]").unwrap_err(), "unexpected `nulls` at 1:2");
+        assert_eq!(compact("[\"\\x\"]").unwrap_err(), "invalid escape in string at 1:2");
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        for number in ["1.2.3", "0x1F", "01", "-", "1.", ".5", "1e", "1e+", "+1", "1-2", "Infinity"]
+        {
+            assert!(compact(&format!("[{number}]")).is_err(), "{number}");
+        }
+        assert_eq!(compact("[1.2.3]").unwrap_err(), "invalid number `1.2.3` at 1:2");
+    }
+}
@@ -98,4 +98,10 @@ export interface Config extends LayoutConfig {
 
 	/** Directive to ignore formatting for entire file. Defaults to "malva-ignore-file". */
 	ignoreFileCommentDirective?: string;
+
+	/** Emit minified output instead of pretty printing. Not supported for Sass indented syntax. Defaults to false. */
+	compact?: boolean;
+
+	/** Remove comments in compact output, except `/*! ... *\/`. Defaults to false. */
+	stripComments?: boolean;
 }
@@ -0,0 +1,129 @@
+//! Compact (minified) output for stylesheets.
+
+use malva::Syntax;
+
+/// Removes insignificant whitespace from already formatted CSS/SCSS/Less.
+///
+/// Comments are kept unless `strip_comments` is set, except `/*! ... */` which are always kept.
+/// Line comments of SCSS and Less are always dropped: they are silent comments, which never reach
+/// the compiled CSS either, and they can't be put on a single line without changing their text.
+/// Plain CSS has no line comments, so `//` is kept there.
+pub(crate) fn compact(src: &str, syntax: Syntax, strip_comments: bool) -> String {
+    let line_comments = matches!(syntax, Syntax::Scss | Syntax::Less);
+    let mut out = String::with_capacity(src.len());
+    let mut pending_space = false;
+    let mut rest = src;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = rest.trim_start();
+            pending_space = true;
+            continue;
+        }
+
+        let token_len = if let Some(comment) = rest.strip_prefix("/*") {
+            let len = comment.find("*/").map_or(rest.len(), |i| i + 4);
+            if strip_comments && !rest.starts_with("/*!") {
+                rest = &rest[len..];
+                continue;
+            }
+            len
+        } else if line_comments && rest.starts_with("//") {
+            rest = &rest[rest.find('\n').unwrap_or(rest.len())..];
+            pending_space = true;
+            continue;
+        } else if c == '"' || c == '\'' {
+            string_len(rest, c)
+        } else if rest.len() >= 4 && rest[..4].eq_ignore_ascii_case("url(") {
+            let inner = rest[4..].trim_start();
+            if inner.starts_with(['"', '\'']) {
+                4
+            } else {
+                rest.find(')').map_or(rest.len(), |i| i + 1)
+            }
+        } else {
+            c.len_utf8()
+        };
+
+        let token = &rest[..token_len];
+        rest = &rest[token_len..];
+
+        if token == "}" && out.ends_with(';') {
+            out.pop();
+        }
+
+        push_token(&mut out, token, &mut pending_space);
+    }
+
+    out
+}
+
+fn push_token(out: &mut String, token: &str, pending_space: &mut bool) {
+    if std::mem::take(pending_space)
+        && !out.is_empty()
+        && !out.ends_with(['{', '}', ';', ',', ':'])
+        && !out.ends_with("*/")
+        && !token.starts_with(['{', '}', ';', ','])
+        && !token.starts_with("/*")
+    {
+        out.push(' ');
+    }
+    out.push_str(token);
+}
+
+fn string_len(src: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in src.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            c if c == quote && !escaped => return i + 1,
+            _ => escaped = false,
+        }
+    }
+    src.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_insignificant_whitespace() {
+        let src = "a > b,\nc:hover {\n  color: red;\n  background: url(http://x/y.png);\n  width: calc(1px + 2px);\n}\n";
+
+        assert_eq!(
+            compact(src, Syntax::Css, false),
+            "a > b,c:hover{color:red;background:url(http://x/y.png);width:calc(1px + 2px)}"
+        );
+    }
+
+    #[test]
+    fn strips_comments() {
+        let src =
+            "/*! keep */\n/* drop */\na {\n  // line\n  content: \"/* not a comment */\";\n}\n";
+
+        assert_eq!(
+            compact(src, Syntax::Scss, true),
+            "/*! keep */a{content:\"/* not a comment */\"}"
+        );
+        assert_eq!(
+            compact(src, Syntax::Scss, false),
+            "/*! keep *//* drop */a{content:\"/* not a comment */\"}"
+        );
+    }
+
+    #[test]
+    fn drops_line_comments() {
+        let src = "a {\n  // see */ here\n  color: red; // red\n  top: 0\n}\n";
+
+        assert_eq!(compact(src, Syntax::Scss, false), "a{color:red;top:0}");
+        assert_eq!(compact(src, Syntax::Less, false), "a{color:red;top:0}");
+    }
+
+    #[test]
+    fn keeps_double_slashes_in_css() {
+        let src = "a {\n  background: url(//host/x);\n  --path: //host/x;\n}\n";
+
+        assert_eq!(compact(src, Syntax::Css, true), "a{background:url(//host/x);--path://host/x}");
+    }
+}
@@ -85,6 +85,15 @@ pub struct MalvaConfig {
 
     #[serde(flatten)]
     language: LanguageOptions,
+
+    /// Emit minified output instead of pretty printing. SCSS and Less line comments are dropped.
+    /// Defaults to false.
+    #[serde(default)]
+    compact: Option<bool>,
+
+    /// Remove comments in compact output, except `/*! ... */`. Defaults to false.
+    #[serde(default, alias = "stripComments")]
+    strip_comments: Option<bool>,
 }
 
 impl MalvaConfig {
@@ -111,6 +120,22 @@ impl MalvaConfig {
         self.layout = self.layout.fill_empty_with(layout);
         self
     }
+
+    #[must_use]
+    pub fn compact(&self) -> bool {
+        self.compact.unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn strip_comments(&self) -> bool {
+        self.strip_comments.unwrap_or_default()
+    }
+
+    /// Whether declarations are reordered by `declarationOrder`.
+    #[must_use]
+    pub fn orders_declarations(&self) -> bool {
+        self.language.declaration_order.is_some()
+    }
 }
 
 impl From<MalvaConfig> for malva::config::FormatOptions {
@@ -0,0 +1,195 @@
+//! Checks that formatting keeps the tokens of stylesheets.
+
+use common::{SourceError, TreeToken, compare_trees, normalize_number, unquote};
+
+use crate::config::MalvaConfig;
+
+/// Punctuation that formatting may surround with whitespace or not.
+const SPACED_PUNCTUATION: [char; 16] =
+    ['{', '}', ';', ':', ',', '>', '+', '~', '(', ')', '[', ']', '=', '/', '*', '!'];
+
+/// Checks that `code`, the formatted version of `src`, has the same tokens.
+///
+/// Comments, whitespace around punctuation, quotes, the spelling of numbers and hex colors,
+/// `from` and `to` keyframe selectors and semicolons before `}` are ignored. When `config`
+/// orders declarations, declarations are compared whatever their order within a block.
+pub fn verify_style_equivalence(
+    src: &str,
+    code: &str,
+    config: &MalvaConfig,
+) -> Result<(), SourceError> {
+    let (mut before, mut after) = (tree_tokens(src), tree_tokens(code));
+    if config.orders_declarations() {
+        before = sort_declarations(before);
+        after = sort_declarations(after);
+    }
+
+    compare_trees("style", src, &before, code, &after)
+}
+
+fn tree_tokens(src: &str) -> Vec<TreeToken> {
+    let mut tokens: Vec<TreeToken> = vec![];
+    let mut spaced = false;
+    let mut rest = src;
+
+    while let Some(c) = rest.chars().next() {
+        let offset = (src.len() - rest.len()) as u32;
+
+        if c.is_whitespace() {
+            rest = rest.trim_start();
+            spaced = true;
+            continue;
+        }
+        if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.find("*/").map_or("", |i| &comment[i + 2..]);
+            spaced = true;
+            continue;
+        }
+        if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |i| &rest[i..]);
+            continue;
+        }
+
+        let len = match c {
+            '"' | '\'' => string_len(rest, c),
+            _ if starts_number(rest) => {
+                let sign = usize::from(rest.starts_with(['+', '-']));
+                sign + rest[sign..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '%')))
+                    .unwrap_or(rest.len() - sign)
+            }
+            c if is_word_char(c) => rest.find(|c: char| !is_word_char(c)).unwrap_or(rest.len()),
+            c => c.len_utf8(),
+        };
+        let (text, next) = rest.split_at(len);
+        rest = next;
+
+        if spaced
+            && !text.starts_with(SPACED_PUNCTUATION)
+            && tokens.last().is_some_and(|last| !last.text.starts_with(SPACED_PUNCTUATION))
+        {
+            tokens.push(TreeToken::new(" ", offset));
+        }
+        spaced = false;
+
+        // `;` before `}` or another `;` is optional.
+        if text == "}" || text == ";" {
+            while tokens.last().is_some_and(|last| last.text == ";") {
+                tokens.pop();
+            }
+        }
+        tokens.push(TreeToken::new(normalize(text), offset));
+    }
+
+    while tokens.last().is_some_and(|last| last.text == ";") {
+        tokens.pop();
+    }
+    tokens
+}
+
+/// Whether `src` starts with a number, e.g. `1px`, `.5em` or `-2`.
+fn starts_number(src: &str) -> bool {
+    let unsigned = src.strip_prefix(['+', '-']).unwrap_or(src);
+    let unsigned = unsigned.strip_prefix('.').unwrap_or(unsigned);
+    unsigned.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn normalize(text: &str) -> String {
+    match text.chars().next() {
+        Some('"' | '\'') => unquote(text),
+        _ if starts_number(text) => {
+            let split =
+                text.find(|c: char| c.is_ascii_alphabetic() || c == '%').unwrap_or(text.len());
+            let (number, unit) = text.split_at(split);
+            format!("{}{}", normalize_number(number), unit.to_ascii_lowercase())
+        }
+        Some('#') if text.len() > 1 && text[1..].chars().all(|c| c.is_ascii_hexdigit()) => {
+            let digits = text[1..].to_ascii_lowercase();
+            match digits.len() {
+                3 | 4 => std::iter::once('#').chain(digits.chars().flat_map(|c| [c, c])).collect(),
+                _ => format!("#{digits}"),
+            }
+        }
+        _ if text.eq_ignore_ascii_case("from") => "0%".into(),
+        _ if text.eq_ignore_ascii_case("to") => "100%".into(),
+        _ => text.to_string(),
+    }
+}
+
+/// Sorts the runs of declarations, i.e. statements without a block, of every block.
+fn sort_declarations(tokens: Vec<TreeToken>) -> Vec<TreeToken> {
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut declarations: Vec<Vec<TreeToken>> = vec![];
+    let mut statement = vec![];
+
+    let flush = |out: &mut Vec<TreeToken>, declarations: &mut Vec<Vec<TreeToken>>| {
+        declarations.sort_by(|a, b| {
+            a.iter().map(|token| &token.text).cmp(b.iter().map(|token| &token.text))
+        });
+        for declaration in declarations.drain(..) {
+            let offset = declaration.last().map_or(0, |token| token.offset);
+            out.extend(declaration);
+            out.push(TreeToken::new(";", offset));
+        }
+    };
+
+    for token in tokens {
+        match token.text.as_str() {
+            ";" => declarations.push(std::mem::take(&mut statement)),
+            "{" | "}" => {
+                if token.text == "}" && !statement.is_empty() {
+                    declarations.push(std::mem::take(&mut statement));
+                }
+                flush(&mut out, &mut declarations);
+                out.append(&mut statement);
+                out.push(token);
+            }
+            _ => statement.push(token),
+        }
+    }
+    flush(&mut out, &mut declarations);
+    out.append(&mut statement);
+    out
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '#' | '.' | '@' | '$' | '&')
+}
+
+fn string_len(src: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in src.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            c if c == quote && !escaped => return i + 1,
+            _ => escaped = false,
+        }
+    }
+    src.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_unchanged_tokens() {
+        let src = "a>b{color:#FFF;margin:.50px 0 /* c */;}";
+        let code = "a > b {\n  color: #ffffff;\n  margin: 0.5px 0;\n}\n";
+        assert_eq!(verify_style_equivalence(src, code, &MalvaConfig::default()), Ok(()));
+    }
+
+    #[test]
+    fn reports_changed_literals() {
+        let err = verify_style_equivalence(
+            "a{content:'x'}",
+            "a {\n  content: \"y\";\n}\n",
+            &MalvaConfig::default(),
+        )
+        .unwrap_err();
+        assert!(
+            err.message.contains("`x` at 1:11 of the source became `y` at 2:12 of the output"),
+            "{err}"
+        );
+    }
+}
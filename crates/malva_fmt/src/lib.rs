@@ -1,6 +1,17 @@
+mod compact;
+#[cfg(feature = "component")]
+mod component;
 pub mod config;
+mod equivalence;
+mod range;
 
+pub use common::{
+    CheckResult, FormattedWithCursor, PrintedRange, SourceError, TextEdit, TextRange,
+};
+#[cfg(feature = "wasm-bindgen")]
+use common::{EncodedRange, OffsetConverter, OffsetEncoding, Position};
 use config::MalvaConfig;
+pub use equivalence::verify_style_equivalence;
 #[cfg(feature = "wasm-bindgen")]
 use wasm_bindgen::prelude::*;
 
@@ -9,8 +20,28 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(typescript_type = "Config")]
     pub type Config;
+
+    #[wasm_bindgen(typescript_type = "TextRange | LineColumnRange")]
+    pub type JsTextRange;
 }
 
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(typescript_custom_section)]
+const RANGE: &'static str = r#"
+import type {
+	CheckResult,
+	FormattedWithCursor,
+	LineColumn,
+	LineColumnRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./text.d.ts";
+
+export type * from "./text.d.ts";
+"#;
+
 /// Formats the given CSS/SCSS/Sass/Less code with the provided Configuration.
 #[cfg(feature = "wasm-bindgen")]
 #[wasm_bindgen(js_name = format)]
@@ -32,6 +63,126 @@ pub fn format_style(
     format_style_with_config(src, filename, config)
 }
 
+/// Formats the smallest rule enclosing a range of the given CSS/SCSS/Sass/Less code with the provided Configuration.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatRange, unchecked_return_type = "PrintedRange")]
+pub fn format_style_range(
+    #[wasm_bindgen(param_description = "The CSS/SCSS/Sass/Less code to format")] src: &str,
+    #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the syntax (e.g., .css, .scss, .sass, .less)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let range: EncodedRange =
+        serde_wasm_bindgen::from_value(range.into()).map_err(|e| e.to_string())?;
+    let range = offsets.range_to_utf8(range)?;
+
+    let config: config::MalvaConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let printed = format_style_range_with_config(src, range, filename, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_printed(printed)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given CSS/SCSS/Sass/Less code, returning where the cursor ends up in the output.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatWithCursor, unchecked_return_type = "FormattedWithCursor")]
+pub fn format_with_cursor(
+    #[wasm_bindgen(param_description = "The CSS/SCSS/Sass/Less code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the syntax (e.g., .css, .scss, .sass, .less)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "number | LineColumn",
+        param_description = "The cursor position, counted as given by `encoding`"
+    )]
+    cursor_offset: JsValue,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the cursor positions are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let config: config::MalvaConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let cursor_offset: Position =
+        serde_wasm_bindgen::from_value(cursor_offset).map_err(|e| e.to_string())?;
+    let cursor_offset = offsets.to_utf8(cursor_offset)?;
+
+    let formatted = format_style_with_cursor(src, filename, cursor_offset, config)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_cursor(formatted)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given CSS/SCSS/Sass/Less code, returning the edits that turn it into the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatEdits, unchecked_return_type = "TextEdit[]")]
+pub fn format_edits(
+    #[wasm_bindgen(param_description = "The CSS/SCSS/Sass/Less code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the syntax (e.g., .css, .scss, .sass, .less)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config: config::MalvaConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let edits = format_style_edits_with_config(src, filename, config)?;
+
+    serde_wasm_bindgen::to_value(&edits).map_err(|e| e.to_string())
+}
+
+/// Checks whether the given CSS/SCSS/Sass/Less code is already formatted, without returning the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(unchecked_return_type = "CheckResult")]
+pub fn check(
+    #[wasm_bindgen(param_description = "The CSS/SCSS/Sass/Less code to check")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the syntax (e.g., .css, .scss, .sass, .less)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config: config::MalvaConfig = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let result = check_style_with_config(src, filename, config)?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
+}
+
 pub fn format_style_with_config(
     src: &str,
     filename: &str,
@@ -39,5 +190,92 @@ pub fn format_style_with_config(
 ) -> Result<String, String> {
     let syntax = malva::detect_syntax(filename).unwrap_or_default();
 
-    malva::format_text(src, syntax, &config.into()).map_err(|e| e.to_string())
+    let compact = config.compact();
+    let strip_comments = config.strip_comments();
+
+    if compact && matches!(syntax, malva::Syntax::Sass) {
+        return Err("compact output is not supported for Sass indented syntax".into());
+    }
+
+    let formatted = malva::format_text(src, syntax, &config.into()).map_err(|e| e.to_string())?;
+
+    if compact {
+        return Ok(compact::compact(&formatted, syntax, strip_comments));
+    }
+
+    Ok(formatted)
+}
+
+/// Formats the given code and maps `cursor_offset` to where the cursor ends up in the output.
+pub fn format_style_with_cursor(
+    src: &str,
+    filename: &str,
+    cursor_offset: u32,
+    config: MalvaConfig,
+) -> Result<FormattedWithCursor, String> {
+    FormattedWithCursor::check_cursor(src, cursor_offset)?;
+
+    let code = format_style_with_config(src, filename, config)?;
+
+    Ok(FormattedWithCursor::from_diff(src, code, cursor_offset))
+}
+
+/// Formats the given code, returning the minimal edits that turn it into the formatted code.
+pub fn format_style_edits_with_config(
+    src: &str,
+    filename: &str,
+    config: MalvaConfig,
+) -> Result<Vec<TextEdit>, String> {
+    let code = format_style_with_config(src, filename, config)?;
+
+    Ok(common::text_edits(src, &code))
+}
+
+/// Checks whether the given code is already formatted, reporting where it first differs otherwise.
+pub fn check_style_with_config(
+    src: &str,
+    filename: &str,
+    config: MalvaConfig,
+) -> Result<CheckResult, String> {
+    let code = format_style_with_config(src, filename, config)?;
+
+    Ok(CheckResult::new(src, &code))
+}
+
+/// Formats the smallest rule enclosing `range`, or the top-level statements it touches.
+///
+/// Only the text of the rule is formatted, indented by its nesting level and within the line width
+/// left at that level. Sass indented syntax is formatted as a whole.
+/// Returns an empty code without a range when `range` touches no statement.
+pub fn format_style_range_with_config(
+    src: &str,
+    range: TextRange,
+    filename: &str,
+    config: MalvaConfig,
+) -> Result<PrintedRange, String> {
+    if config.compact() {
+        return Err("compact output cannot be applied to a range".into());
+    }
+    range.check(src)?;
+
+    let syntax = malva::detect_syntax(filename).unwrap_or_default();
+    let mut options: malva::config::FormatOptions = config.into();
+
+    if matches!(syntax, malva::Syntax::Sass) {
+        let code = malva::format_text(src, syntax, &options).map_err(|e| e.to_string())?;
+        return Ok(PrintedRange { code, range: Some(TextRange::whole(src)) });
+    }
+
+    let Some(rule) = range::enclosing_rule(src, range) else {
+        return Ok(PrintedRange { code: String::new(), range: None });
+    };
+
+    let layout = &mut options.layout;
+    let unit = if layout.use_tabs { "\t".to_string() } else { " ".repeat(layout.indent_width) };
+    layout.print_width = layout.print_width.saturating_sub(rule.depth * layout.indent_width);
+
+    let code = malva::format_text(&src[rule.start..rule.end], syntax, &options)
+        .map_err(|e| e.to_string())?;
+
+    Ok(PrintedRange::indented(src, rule.start, rule.end, &code, &unit.repeat(rule.depth)))
 }
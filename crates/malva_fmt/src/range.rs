@@ -0,0 +1,141 @@
+//! Range formatting for stylesheets.
+
+use common::TextRange;
+
+/// A statement: a declaration, an at-rule without block, or a rule with its block.
+struct Item {
+    start: usize,
+    end: usize,
+    children: Option<Vec<Item>>,
+}
+
+/// A rule found by [`enclosing_rule`], with the number of blocks it is nested in.
+pub(crate) struct Enclosing {
+    pub start: usize,
+    pub end: usize,
+    pub depth: usize,
+}
+
+/// Finds the smallest rule enclosing `range`.
+///
+/// Falls back to the top-level statements touched by `range` when no rule encloses it.
+/// Returns `None` when no statement is touched.
+pub(crate) fn enclosing_rule(src: &str, range: TextRange) -> Option<Enclosing> {
+    let mut pos = 0;
+    let items = parse_items(src, &mut pos);
+
+    find_rule(&items, range, 0).or_else(|| {
+        let mut touched = items.iter().filter(|item| range.touches(item.start, item.end));
+        let first = touched.next()?;
+        let last = touched.next_back().unwrap_or(first);
+        Some(Enclosing { start: first.start, end: last.end, depth: 0 })
+    })
+}
+
+fn find_rule(items: &[Item], range: TextRange, depth: usize) -> Option<Enclosing> {
+    let (start, end) = (range.start as usize, range.end as usize);
+
+    items.iter().find_map(|item| {
+        let children = item.children.as_ref()?;
+        (item.start <= start && end <= item.end).then(|| {
+            find_rule(children, range, depth + 1).unwrap_or(Enclosing {
+                start: item.start,
+                end: item.end,
+                depth,
+            })
+        })
+    })
+}
+
+/// Parses statements until the end of the source or the `}` closing the current block.
+fn parse_items(src: &str, pos: &mut usize) -> Vec<Item> {
+    let bytes = src.as_bytes();
+    let mut items = vec![];
+    let mut start = None;
+
+    while let Some(&byte) = bytes.get(*pos) {
+        match byte {
+            b'/' if bytes.get(*pos + 1) == Some(&b'*') => {
+                *pos = src[*pos + 2..].find("*/").map_or(src.len(), |i| *pos + i + 4);
+                continue;
+            }
+            b'/' if bytes.get(*pos + 1) == Some(&b'/') && start.is_none() => {
+                *pos = src[*pos..].find('\n').map_or(src.len(), |i| *pos + i);
+                continue;
+            }
+            b'"' | b'\'' => {
+                start.get_or_insert(*pos);
+                *pos = string_end(src, *pos);
+                continue;
+            }
+            b'{' if *pos > 0 && matches!(bytes[*pos - 1], b'#' | b'@') => {
+                // Interpolation, e.g. `#{$var}` in SCSS or `@{var}` in Less.
+                *pos = src[*pos..].find('}').map_or(src.len(), |i| *pos + i + 1);
+                continue;
+            }
+            b'(' if *pos >= 3 && bytes[*pos - 3..*pos].eq_ignore_ascii_case(b"url") => {
+                start.get_or_insert(*pos);
+                *pos = src[*pos..].find(')').map_or(src.len(), |i| *pos + i + 1);
+                continue;
+            }
+            b'{' => {
+                let item_start = start.take().unwrap_or(*pos);
+                *pos += 1;
+                let children = parse_items(src, pos);
+                *pos = (*pos + 1).min(src.len());
+                items.push(Item { start: item_start, end: *pos, children: Some(children) });
+                continue;
+            }
+            b';' => {
+                if let Some(item_start) = start.take() {
+                    items.push(Item { start: item_start, end: *pos + 1, children: None });
+                }
+            }
+            b'}' => break,
+            byte if byte.is_ascii_whitespace() => {}
+            _ => {
+                start.get_or_insert(*pos);
+            }
+        }
+        *pos += 1;
+    }
+
+    if let Some(item_start) = start {
+        let end = item_start + src[item_start..*pos].trim_end().len();
+        items.push(Item { start: item_start, end, children: None });
+    }
+
+    items
+}
+
+fn string_end(src: &str, start: usize) -> usize {
+    let quote = src.as_bytes()[start];
+    let mut escaped = false;
+    for (i, &byte) in src.as_bytes().iter().enumerate().skip(start + 1) {
+        match byte {
+            b'\\' if !escaped => escaped = true,
+            byte if byte == quote && !escaped => return i + 1,
+            _ => escaped = false,
+        }
+    }
+    src.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_smallest_enclosing_rule() {
+        let src = "a { color: red; }\n@media print {\n  .b { content: \"}\"; margin: 0 }\n}\n";
+        let offset = src.find("margin").unwrap() as u32;
+
+        let rule = enclosing_rule(src, TextRange { start: offset, end: offset + 1 }).unwrap();
+        assert_eq!(&src[rule.start..rule.end], ".b { content: \"}\"; margin: 0 }");
+        assert_eq!(rule.depth, 1);
+
+        let rule = enclosing_rule(src, TextRange { start: 2, end: 30 }).unwrap();
+        assert_eq!(&src[rule.start..rule.end], &src[..src.len() - 1]);
+        assert_eq!(rule.depth, 0);
+    }
+}
@@ -1,7 +1,7 @@
 /* @ts-self-types="./markup_fmt_web.d.ts" */
 import init from "./markup_fmt_bg.wasm?init";
 import * as import_bg from "./markup_fmt_bg.js";
-const { __wbg_set_wasm, format, ...wasmImport } = import_bg;
+const { __wbg_set_wasm, format, formatRange, formatWithCursor, formatEdits, check, ...wasmImport } = import_bg;
 
 let wasm, wasmModule;
 
@@ -35,4 +35,4 @@ export function initSync(module) {
 	return finalize_init(instance, module);
 }
 
-export { format };
+export { format, formatRange, formatWithCursor, formatEdits, check };
@@ -0,0 +1,58 @@
+//! The WebAssembly component, exporting the `formatter` interface of the WIT world in `wit/`.
+//!
+//! As in the npm package, `<script>` and `<style>` blocks are kept as they are.
+
+use common::component::{parse_config, types};
+
+use crate::FormatMarkup;
+use crate::config::MarkupConfig;
+
+wit_bindgen::generate!({
+    world: "markup-formatter",
+    path: "../../wit",
+    with: { "wasm-fmt:formatter/types": common::component::types },
+});
+
+struct Component;
+
+impl exports::wasm_fmt::formatter::formatter::Guest for Component {
+    fn format(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<String, String> {
+        let config: MarkupConfig = parse_config(config.as_ref())?;
+
+        FormatMarkup::new(&src, &filename).config(config.into()).format()
+    }
+
+    fn format_range(
+        src: String,
+        filename: String,
+        range: types::TextRange,
+        config: Option<types::Config>,
+    ) -> Result<types::PrintedRange, String> {
+        let config: MarkupConfig = parse_config(config.as_ref())?;
+
+        let printed = FormatMarkup::new(&src, &filename)
+            .config(config.into())
+            .range(range.into())
+            .format_range()?;
+
+        Ok(printed.into())
+    }
+
+    fn check(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<types::CheckResult, String> {
+        let config: MarkupConfig = parse_config(config.as_ref())?;
+
+        let result = FormatMarkup::new(&src, &filename).config(config.into()).check()?;
+
+        Ok(result.into())
+    }
+}
+
+export!(Component);
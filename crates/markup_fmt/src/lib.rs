@@ -1,7 +1,17 @@
+#[cfg(feature = "component")]
+mod component;
 pub mod config;
+mod range;
 
+pub use common::{
+    CheckResult, FormattedWithCursor, PrintedRange, SourceError, TextEdit, TextRange,
+};
 pub use markup_fmt_core::{Hints, Language};
 
+use common::Location;
+
+#[cfg(feature = "wasm-bindgen")]
+use common::{EncodedRange, OffsetConverter, OffsetEncoding, Position};
 #[cfg(feature = "wasm-bindgen")]
 use wasm_bindgen::prelude::*;
 
@@ -10,8 +20,28 @@ use wasm_bindgen::prelude::*;
 extern "C" {
     #[wasm_bindgen(typescript_type = "Config")]
     pub type Config;
+
+    #[wasm_bindgen(typescript_type = "TextRange | LineColumnRange")]
+    pub type JsTextRange;
 }
 
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(typescript_custom_section)]
+const RANGE: &'static str = r#"
+import type {
+	CheckResult,
+	FormattedWithCursor,
+	LineColumn,
+	LineColumnRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./text.d.ts";
+
+export type * from "./text.d.ts";
+"#;
+
 /// Formats the given HTML/Vue/Svelte/Astro code with the provided Configuration.
 #[cfg(feature = "wasm-bindgen")]
 #[wasm_bindgen(js_name = format)]
@@ -33,6 +63,127 @@ pub fn format_markup(
     FormatMarkup::new(src, filename).config(config).format()
 }
 
+/// Formats the smallest element enclosing a range of the given HTML/Vue/Svelte/Astro code with the provided Configuration.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatRange, unchecked_return_type = "PrintedRange")]
+pub fn format_markup_range(
+    #[wasm_bindgen(param_description = "The HTML/Vue/Svelte/Astro code to format")] src: &str,
+    #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the template language (e.g., .html, .vue, .svelte, .astro)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let range: EncodedRange =
+        serde_wasm_bindgen::from_value(range.into()).map_err(|e| e.to_string())?;
+    let range = offsets.range_to_utf8(range)?;
+
+    let config: markup_fmt_core::config::FormatOptions = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let printed = FormatMarkup::new(src, filename).config(config).range(range).format_range()?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_printed(printed)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given HTML/Vue/Svelte/Astro code, returning where the cursor ends up in the output.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatWithCursor, unchecked_return_type = "FormattedWithCursor")]
+pub fn format_markup_with_cursor(
+    #[wasm_bindgen(param_description = "The HTML/Vue/Svelte/Astro code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the template language (e.g., .html, .vue, .svelte, .astro)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "number | LineColumn",
+        param_description = "The cursor position, counted as given by `encoding`"
+    )]
+    cursor_offset: JsValue,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the cursor positions are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let config: markup_fmt_core::config::FormatOptions = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let cursor_offset: Position =
+        serde_wasm_bindgen::from_value(cursor_offset).map_err(|e| e.to_string())?;
+    let cursor_offset = offsets.to_utf8(cursor_offset)?;
+
+    let formatted =
+        FormatMarkup::new(src, filename).config(config).format_with_cursor(cursor_offset)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_cursor(formatted)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given HTML/Vue/Svelte/Astro code, returning the edits that turn it into the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatEdits, unchecked_return_type = "TextEdit[]")]
+pub fn format_markup_edits(
+    #[wasm_bindgen(param_description = "The HTML/Vue/Svelte/Astro code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the template language (e.g., .html, .vue, .svelte, .astro)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config: markup_fmt_core::config::FormatOptions = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let edits = FormatMarkup::new(src, filename).config(config).format_edits()?;
+
+    serde_wasm_bindgen::to_value(&edits).map_err(|e| e.to_string())
+}
+
+/// Checks whether the given HTML/Vue/Svelte/Astro code is already formatted, without returning the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = check, unchecked_return_type = "CheckResult")]
+pub fn check_markup(
+    #[wasm_bindgen(param_description = "The HTML/Vue/Svelte/Astro code to check")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the template language (e.g., .html, .vue, .svelte, .astro)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config: markup_fmt_core::config::FormatOptions = config
+        .as_ref()
+        .map(|x| serde_wasm_bindgen::from_value(x.into()))
+        .transpose()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+
+    let result = FormatMarkup::new(src, filename).config(config).check()?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
+}
+
 /// Trait for formatting embedded code.
 pub trait EmbeddedFormatter {
     fn format(&self, src: &str, hints: Hints) -> Result<String, String>;
@@ -53,12 +204,19 @@ pub struct FormatMarkup<'a, F = NoneFormatter> {
     filename: &'a str,
     config: markup_fmt_core::config::FormatOptions,
     embed_formatter: F,
+    range: Option<TextRange>,
 }
 
 impl<'a> FormatMarkup<'a, NoneFormatter> {
     #[must_use]
     pub fn new(src: &'a str, filename: &'a str) -> Self {
-        Self { src, filename, config: Default::default(), embed_formatter: NoneFormatter }
+        Self {
+            src,
+            filename,
+            config: Default::default(),
+            embed_formatter: NoneFormatter,
+            range: None,
+        }
     }
 
     /// Set formatter for embedded code (script, style, json, etc.).
@@ -70,6 +228,7 @@ impl<'a> FormatMarkup<'a, NoneFormatter> {
             filename: self.filename,
             config: self.config,
             embed_formatter: formatter,
+            range: self.range,
         }
     }
 }
@@ -79,10 +238,22 @@ impl<F> FormatMarkup<'_, F> {
         self.config = config;
         self
     }
+
+    /// Set the range formatted by [`FormatMarkup::format_range`], in UTF-8 byte offsets.
+    #[must_use]
+    pub fn range(mut self, range: TextRange) -> Self {
+        self.range = Some(range);
+        self
+    }
 }
 
 impl<F: EmbeddedFormatter> FormatMarkup<'_, F> {
     pub fn format(self) -> Result<String, String> {
+        self.try_format().map_err(String::from)
+    }
+
+    /// Like [`FormatMarkup::format`], with the location of syntax errors in the source.
+    pub fn try_format(self) -> Result<String, SourceError> {
         let language = markup_fmt_core::detect_language(self.filename)
             .unwrap_or(markup_fmt_core::Language::Html);
         let Self { src, config: markup_config, embed_formatter, .. } = self;
@@ -90,7 +261,106 @@ impl<F: EmbeddedFormatter> FormatMarkup<'_, F> {
         markup_fmt_core::format_text(src, language, &markup_config, |embed_src, hints| {
             format_embedded(embed_src, hints, &embed_formatter)
         })
-        .map_err(|e| format!("{e:?}"))
+        .map_err(|e| {
+            let location = match &e {
+                markup_fmt_core::FormatError::Syntax(error) => {
+                    Some(Location { line: error.line as u32, column: error.column as u32 })
+                }
+                // Errors of embedded blocks point into the blocks.
+                _ => None,
+            };
+            SourceError { message: format!("{e:?}"), location }
+        })
+    }
+
+    /// Format the whole document and map `cursor_offset` to where the cursor ends up in the output.
+    ///
+    /// The cursor is mapped by diffing the source and the output, so a cursor inside an embedded
+    /// `<script>` or `<style>` block follows the code formatted by the embedded formatter.
+    pub fn format_with_cursor(self, cursor_offset: u32) -> Result<FormattedWithCursor, String> {
+        let src = self.src;
+        FormattedWithCursor::check_cursor(src, cursor_offset)?;
+
+        let code = self.format()?;
+
+        Ok(FormattedWithCursor::from_diff(src, code, cursor_offset))
+    }
+
+    /// Format the whole document, returning the minimal edits that turn it into the formatted code.
+    pub fn format_edits(self) -> Result<Vec<TextEdit>, String> {
+        let src = self.src;
+        let code = self.format()?;
+
+        Ok(common::text_edits(src, &code))
+    }
+
+    /// Check whether the code is already formatted, reporting where it first differs otherwise.
+    pub fn check(self) -> Result<CheckResult, String> {
+        let src = self.src;
+        let code = self.format()?;
+
+        Ok(CheckResult::new(src, &code))
+    }
+
+    /// Format the smallest element enclosing the configured range.
+    ///
+    /// Only the text of the element is formatted, indented by its nesting level and within
+    /// the line width left at that level. A range within a `<script>` or `<style>` block
+    /// formats just that block. Without a range, the whole document is formatted.
+    /// When no element encloses the range, an empty code without a range is returned.
+    pub fn format_range(self) -> Result<PrintedRange, String> {
+        let src = self.src;
+        let Some(range) = self.range else {
+            return Ok(PrintedRange { code: self.format()?, range: Some(TextRange::whole(src)) });
+        };
+        range.check(src)?;
+        let Some(element) = range::enclosing_element(src, range) else {
+            return Ok(PrintedRange { code: String::new(), range: None });
+        };
+
+        let Self { filename, mut config, embed_formatter, .. } = self;
+        let layout = &mut config.layout;
+        let unit = if layout.use_tabs { "\t".to_string() } else { " ".repeat(layout.indent_width) };
+        layout.print_width = layout.print_width.saturating_sub(element.depth * layout.indent_width);
+
+        // Elements of a Vue template are only parsed as markup within a `<template>` block.
+        let slice = &src[element.start..element.end];
+        let in_template = element.depth > 0
+            && matches!(
+                markup_fmt_core::detect_language(filename),
+                Some(markup_fmt_core::Language::Vue)
+            );
+        let wrapped;
+        let slice = if in_template {
+            layout.print_width += layout.indent_width;
+            wrapped = format!("<template>\n{slice}\n</template>\n");
+            &wrapped
+        } else {
+            slice
+        };
+
+        let formatted =
+            FormatMarkup { src: slice, filename, config, embed_formatter, range: None }.format()?;
+
+        let code = if in_template {
+            let lines: Vec<_> = formatted.trim().split('\n').collect();
+            let inner = lines.get(1..lines.len().saturating_sub(1)).unwrap_or_default();
+            inner
+                .iter()
+                .map(|line| line.strip_prefix(&unit).unwrap_or(line))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            formatted
+        };
+
+        Ok(PrintedRange::indented(
+            src,
+            element.start,
+            element.end,
+            &code,
+            &unit.repeat(element.depth),
+        ))
     }
 }
 
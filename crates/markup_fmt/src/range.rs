@@ -0,0 +1,180 @@
+//! Range formatting for markup.
+
+use common::TextRange;
+
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is not markup.
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["script", "style", "textarea", "title"];
+
+/// Elements whose end tag may be omitted before a sibling of the same name.
+const SIBLING_CLOSED_ELEMENTS: [&str; 7] = ["li", "dt", "dd", "p", "option", "tr", "td"];
+
+/// An element found by [`enclosing_element`], with the number of elements it is nested in.
+pub(crate) struct Enclosing {
+    pub start: usize,
+    pub end: usize,
+    pub depth: usize,
+}
+
+/// Finds the smallest element enclosing `range`, e.g. the `<script>` or `<style>` block
+/// of a component when the range lies within its code.
+pub(crate) fn enclosing_element(src: &str, range: TextRange) -> Option<Enclosing> {
+    let (start, end) = (range.start as usize, range.end as usize);
+    let elements = elements(src);
+
+    let (start, end) = elements
+        .iter()
+        .copied()
+        .filter(|&(element_start, element_end)| element_start <= start && end <= element_end)
+        .min_by_key(|&(element_start, element_end)| element_end - element_start)?;
+
+    let depth = elements
+        .iter()
+        .filter(|&&(outer_start, outer_end)| {
+            outer_start <= start && end <= outer_end && (outer_start, outer_end) != (start, end)
+        })
+        .count();
+
+    Some(Enclosing { start, end, depth })
+}
+
+/// Collects the spans of all elements, from their start tag to the end of their end tag.
+fn elements(src: &str) -> Vec<(usize, usize)> {
+    let bytes = src.as_bytes();
+    let mut elements = vec![];
+    let mut open: Vec<(&str, usize)> = vec![];
+    let mut pos = frontmatter_end(src);
+
+    while let Some(offset) = src[pos..].find('<') {
+        let tag_start = pos + offset;
+        let rest = &src[tag_start..];
+
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(src.len(), |i| tag_start + i + 3);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos = rest.find('>').map_or(src.len(), |i| tag_start + i + 1);
+            continue;
+        }
+
+        let closing = rest.starts_with("</");
+        let name_start = tag_start + if closing { 2 } else { 1 };
+        let name_len = src[name_start..]
+            .find(|c: char| c.is_ascii_whitespace() || matches!(c, '/' | '>'))
+            .unwrap_or(src.len() - name_start);
+        let name = &src[name_start..name_start + name_len];
+
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            pos = tag_start + 1;
+            continue;
+        }
+
+        let tag_end = tag_end(src, name_start + name_len);
+        pos = tag_end;
+
+        if closing {
+            if let Some(index) = open.iter().rposition(|(open, _)| open.eq_ignore_ascii_case(name))
+            {
+                // Elements left open inside, e.g. `<li>` without `</li>`, end here too.
+                for (_, element_start) in open.drain(index..) {
+                    elements.push((element_start, tag_end));
+                }
+            }
+            continue;
+        }
+
+        let self_closing = bytes[..tag_end].ends_with(b"/>");
+        if self_closing || VOID_ELEMENTS.iter().any(|void| void.eq_ignore_ascii_case(name)) {
+            elements.push((tag_start, tag_end));
+            continue;
+        }
+
+        if RAW_TEXT_ELEMENTS.iter().any(|raw| raw.eq_ignore_ascii_case(name)) {
+            let close = format!("</{}", name.to_ascii_lowercase());
+            let content_end =
+                src[tag_end..].to_ascii_lowercase().find(&close).map_or(src.len(), |i| tag_end + i);
+            let element_end =
+                src[content_end..].find('>').map_or(src.len(), |i| content_end + i + 1);
+            elements.push((tag_start, element_end));
+            pos = element_end;
+            continue;
+        }
+
+        if SIBLING_CLOSED_ELEMENTS.iter().any(|sibling| sibling.eq_ignore_ascii_case(name))
+            && let Some(&(_, sibling_start)) =
+                open.last().filter(|(open, _)| open.eq_ignore_ascii_case(name))
+        {
+            open.pop();
+            let sibling_end = sibling_start + src[sibling_start..tag_start].trim_end().len();
+            elements.push((sibling_start, sibling_end));
+        }
+
+        open.push((name, tag_start));
+    }
+
+    elements.extend(open.into_iter().map(|(_, start)| (start, src.len())));
+    elements
+}
+
+/// Finds the end of a tag, skipping quoted attribute values and `{...}` expressions.
+fn tag_end(src: &str, from: usize) -> usize {
+    let mut quote = None;
+    let mut depth = 0usize;
+
+    for (i, c) in src[from..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if depth == 0 => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => depth = depth.saturating_sub(1),
+            (None, '>') if depth == 0 => return from + i + 1,
+            _ => {}
+        }
+    }
+
+    src.len()
+}
+
+/// Skips the `---` frontmatter of Astro components.
+fn frontmatter_end(src: &str) -> usize {
+    let trimmed = src.trim_start();
+    let Some(rest) = trimmed.strip_prefix("---") else {
+        return 0;
+    };
+    let start = src.len() - rest.len();
+    rest.find("\n---").map_or(0, |i| start + i + 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_smallest_enclosing_element() {
+        let src = "<template>\n  <ul>\n    <li>a\n    <li :x=\"a > b\">b<br></li>\n  </ul>\n</template>\n<script setup>\nconst a = '<div>';\n</script>\n";
+
+        let offset = src.find("a\n").unwrap() as u32;
+        let element = enclosing_element(src, TextRange { start: offset, end: offset }).unwrap();
+        assert_eq!(&src[element.start..element.end], "<li>a");
+        assert_eq!(element.depth, 2);
+
+        let offset = src.find("b<br>").unwrap() as u32;
+        let element = enclosing_element(src, TextRange { start: offset, end: offset }).unwrap();
+        assert_eq!(&src[element.start..element.end], "<li :x=\"a > b\">b<br></li>");
+        assert_eq!(element.depth, 2);
+
+        let offset = src.find("const").unwrap() as u32;
+        let element = enclosing_element(src, TextRange { start: offset, end: offset }).unwrap();
+        assert_eq!(
+            &src[element.start..element.end],
+            "<script setup>\nconst a = '<div>';\n</script>"
+        );
+        assert_eq!(element.depth, 0);
+    }
+}
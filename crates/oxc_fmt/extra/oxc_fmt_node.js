@@ -1,7 +1,7 @@
 /* @ts-self-types="./oxc_fmt.d.ts" */
 import { readFileSync } from "node:fs";
 import * as import_bg from "./oxc_fmt_bg.js";
-const { __wbg_set_wasm, format, ...wasmImport } = import_bg;
+const { __wbg_set_wasm, format, organizeImports, formatRange, formatWithCursor, formatEdits, check, ...wasmImport } = import_bg;
 
 const wasmUrl = new URL("oxc_fmt_bg.wasm", import.meta.url);
 const wasmBytes = readFileSync(wasmUrl);
@@ -18,4 +18,4 @@ const instance = new WebAssembly.Instance(wasmModule, getImports());
 const wasm = instance.exports;
 __wbg_set_wasm(wasm);
 
-export { format };
+export { format, organizeImports, formatRange, formatWithCursor, formatEdits, check };
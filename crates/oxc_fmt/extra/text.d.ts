@@ -0,0 +1 @@
+../../common/extra/text.d.ts
\ No newline at end of file
@@ -0,0 +1,53 @@
+//! The WebAssembly component, exporting the `formatter` interface of the WIT world in `wit/`.
+
+use common::component::{parse_config, types};
+
+use crate::FormatScript;
+
+wit_bindgen::generate!({
+    world: "oxc-formatter",
+    path: "../../wit",
+    with: { "wasm-fmt:formatter/types": common::component::types },
+});
+
+struct Component;
+
+impl exports::wasm_fmt::formatter::formatter::Guest for Component {
+    fn format(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<String, String> {
+        FormatScript::new(&src, &filename).config(parse_config(config.as_ref())?)?.format()
+    }
+
+    fn format_range(
+        src: String,
+        filename: String,
+        range: types::TextRange,
+        config: Option<types::Config>,
+    ) -> Result<types::PrintedRange, String> {
+        let range = common::TextRange::from(range);
+        range.check(&src)?;
+
+        let printed = FormatScript::new(&src, &filename)
+            .config(parse_config(config.as_ref())?)?
+            .range(range)
+            .format_range()?;
+
+        Ok(printed.into())
+    }
+
+    fn check(
+        src: String,
+        filename: String,
+        config: Option<types::Config>,
+    ) -> Result<types::CheckResult, String> {
+        let result =
+            FormatScript::new(&src, &filename).config(parse_config(config.as_ref())?)?.check()?;
+
+        Ok(result.into())
+    }
+}
+
+export!(Component);
@@ -15,6 +15,25 @@ pub struct OxFmtOptions {
     inner: FormatOptions,
 }
 
+impl OxFmtOptions {
+    #[must_use]
+    pub fn with_line_width(mut self, line_width: u16) -> Self {
+        self.layout = self.layout.with_line_width(line_width);
+        self
+    }
+
+    /// Whether formatting sorts imports.
+    pub(crate) fn sorts_imports(&self) -> bool {
+        self.inner.sort_imports.is_some()
+    }
+
+    #[must_use]
+    pub fn fill_empty_layout_with(mut self, layout: &LayoutConfig) -> Self {
+        self.layout = self.layout.fill_empty_with(layout);
+        self
+    }
+}
+
 impl TryFrom<OxFmtOptions> for oxc_formatter::FormatOptions {
     type Error = String;
 
@@ -189,7 +208,7 @@ pub struct FormatOptions {
         alias = "sortImports",
         alias = "experimentalSortImports",
         default,
-        deserialize_with = "deserialize_optional_via_def::<_, SortImportsOptionsDef, _>"
+        deserialize_with = "deserialize_sort_imports"
     )]
     pub sort_imports: Option<oxc_formatter::SortImportsOptions>,
 
@@ -215,96 +234,58 @@ pub struct FormatOptions {
 // Sort Imports Options
 // =============================================================================
 
-/// Local definition for SortImportsOptions to enable deserialization.
-/// This mirrors `oxc_formatter::SortImportsOptions`.
-#[derive(Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SortImportsOptionsDef {
-    #[serde(default)]
-    pub partition_by_newline: bool,
-    #[serde(default)]
-    pub partition_by_comment: bool,
-    #[serde(default)]
-    pub sort_side_effects: bool,
-    #[serde(default, deserialize_with = "deserialize_from_str")]
-    pub order: oxc_formatter::SortOrder,
-    #[serde(default = "default_true")]
-    pub ignore_case: bool,
-    #[serde(default = "default_true")]
-    pub newlines_between: bool,
-    #[serde(default = "oxc_formatter::default_internal_patterns")]
-    pub internal_pattern: Vec<String>,
-    #[serde(default, deserialize_with = "groups::deserialize")]
-    pub groups: ParsedGroups,
-    #[serde(default)]
-    pub custom_groups: Vec<CustomGroupDefinitionDef>,
-}
-
-/// Parsed groups result containing both groups and newline boundary overrides.
-#[derive(Debug, Default, Clone)]
-pub struct ParsedGroups {
-    pub groups: Vec<Vec<oxc_formatter::GroupEntry>>,
-    pub newline_boundary_overrides: Vec<Option<bool>>,
-}
-
-impl Default for SortImportsOptionsDef {
-    fn default() -> Self {
-        Self {
-            partition_by_newline: false,
-            partition_by_comment: false,
-            sort_side_effects: false,
-            order: oxc_formatter::SortOrder::default(),
-            ignore_case: true,
-            newlines_between: true,
-            internal_pattern: oxc_formatter::default_internal_patterns(),
-            groups: ParsedGroups {
-                groups: oxc_formatter::default_groups(),
-                newline_boundary_overrides: vec![],
-            },
-            custom_groups: vec![],
-        }
-    }
-}
-
-impl From<SortImportsOptionsDef> for oxc_formatter::SortImportsOptions {
-    fn from(def: SortImportsOptionsDef) -> Self {
-        Self {
-            partition_by_newline: def.partition_by_newline,
-            partition_by_comment: def.partition_by_comment,
-            sort_side_effects: def.sort_side_effects,
-            order: def.order,
-            ignore_case: def.ignore_case,
-            newlines_between: def.newlines_between,
-            internal_pattern: def.internal_pattern,
-            groups: def.groups.groups,
-            custom_groups: def.custom_groups.into_iter().map(Into::into).collect(),
-            newline_boundary_overrides: def.groups.newline_boundary_overrides,
-        }
-    }
-}
-
-#[derive(Deserialize, Default, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CustomGroupDefinitionDef {
-    #[serde(default)]
-    pub group_name: String,
-    #[serde(default)]
-    pub element_name_pattern: Vec<String>,
-    #[serde(default, deserialize_with = "import_selector::deserialize")]
-    pub selector: Option<oxc_formatter::ImportSelector>,
-    #[serde(default, deserialize_with = "import_modifiers::deserialize")]
-    pub modifiers: Vec<oxc_formatter::ImportModifier>,
-}
-
-impl From<CustomGroupDefinitionDef> for oxc_formatter::CustomGroupDefinition {
-    fn from(def: CustomGroupDefinitionDef) -> Self {
-        Self {
-            group_name: def.group_name,
-            element_name_pattern: def.element_name_pattern,
-            selector: def.selector,
-            modifiers: def.modifiers,
-        }
-    }
+/// Maps the shared `sortImports` options to those of `oxc_formatter`.
+pub(crate) fn sort_imports_options(
+    options: common::SortImportsOptions,
+) -> Result<oxc_formatter::SortImportsOptions, String> {
+    let custom_groups = options
+        .custom_groups
+        .into_iter()
+        .map(|group| {
+            let selector = group
+                .selector
+                .map(|s| {
+                    oxc_formatter::ImportSelector::parse(&s)
+                        .ok_or_else(|| format!("Invalid import selector: {s}"))
+                })
+                .transpose()?;
+            let modifiers = group
+                .modifiers
+                .iter()
+                .map(|s| {
+                    oxc_formatter::ImportModifier::parse(s)
+                        .ok_or_else(|| format!("Invalid import modifier: {s}"))
+                })
+                .collect::<Result<_, String>>()?;
+            Ok(oxc_formatter::CustomGroupDefinition {
+                group_name: group.group_name,
+                element_name_pattern: group.element_name_pattern,
+                selector,
+                modifiers,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(oxc_formatter::SortImportsOptions {
+        partition_by_newline: options.partition_by_newline,
+        partition_by_comment: options.partition_by_comment,
+        sort_side_effects: options.sort_side_effects,
+        order: match options.order {
+            common::SortOrder::Asc => oxc_formatter::SortOrder::Asc,
+            common::SortOrder::Desc => oxc_formatter::SortOrder::Desc,
+        },
+        ignore_case: options.ignore_case,
+        newlines_between: options.newlines_between,
+        internal_pattern: options.internal_pattern,
+        groups: options
+            .groups
+            .groups
+            .iter()
+            .map(|group| group.iter().map(|s| oxc_formatter::GroupEntry::parse(s)).collect())
+            .collect(),
+        custom_groups,
+        newline_boundary_overrides: options.groups.newline_boundary_overrides,
+    })
 }
 
 // =============================================================================
@@ -419,20 +400,6 @@ impl FromUseSingle for oxc_formatter::QuoteStyle {
 // Helper Functions
 // =============================================================================
 
-fn default_true() -> bool {
-    true
-}
-
-fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
-where
-    D: Deserializer<'de>,
-    T: FromStr,
-    T::Err: std::fmt::Display,
-{
-    let s = String::deserialize(deserializer)?;
-    T::from_str(&s).map_err(serde::de::Error::custom)
-}
-
 /// Deserialize an optional value from a string using FromStr.
 fn deserialize_optional_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
 where
@@ -455,6 +422,17 @@ where
     Ok(def.map(Into::into))
 }
 
+fn deserialize_sort_imports<'de, D>(
+    deserializer: D,
+) -> Result<Option<oxc_formatter::SortImportsOptions>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<common::SortImportsOptions>::deserialize(deserializer)?
+        .map(|options| sort_imports_options(options).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
 fn deserialize_optional_comment_line_strategy<'de, D>(
     deserializer: D,
 ) -> Result<Option<oxc_formatter::CommentLineStrategy>, D::Error>
@@ -502,137 +480,3 @@ where
         None => None,
     })
 }
-
-// =============================================================================
-// Custom Deserialization Modules
-// =============================================================================
-
-/// Custom deserialization module for ImportSelector
-mod import_selector {
-    use serde::Deserialize;
-    use serde::Deserializer;
-
-    pub fn deserialize<'de, D>(
-        deserializer: D,
-    ) -> Result<Option<oxc_formatter::ImportSelector>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = Option::<String>::deserialize(deserializer)?;
-        match s {
-            Some(s) => oxc_formatter::ImportSelector::parse(&s)
-                .map(Some)
-                .ok_or_else(|| serde::de::Error::custom(format!("Invalid import selector: {s}"))),
-            None => Ok(None),
-        }
-    }
-}
-
-/// Custom deserialization module for ImportModifier
-mod import_modifiers {
-    use serde::Deserializer;
-    use serde::de::{SeqAccess, Visitor};
-    use std::fmt;
-
-    pub fn deserialize<'de, D>(
-        deserializer: D,
-    ) -> Result<Vec<oxc_formatter::ImportModifier>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct ImportModifiersVisitor;
-
-        impl<'de> Visitor<'de> for ImportModifiersVisitor {
-            type Value = Vec<oxc_formatter::ImportModifier>;
-
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("an array of import modifier strings")
-            }
-
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-            where
-                A: SeqAccess<'de>,
-            {
-                let mut items = Vec::new();
-
-                while let Some(name) = seq.next_element::<String>()? {
-                    match oxc_formatter::ImportModifier::parse(&name) {
-                        Some(item) => items.push(item),
-                        None => {
-                            return Err(serde::de::Error::custom(format!(
-                                "Invalid import modifier: {name}"
-                            )));
-                        }
-                    }
-                }
-
-                Ok(items)
-            }
-        }
-
-        deserializer.deserialize_seq(ImportModifiersVisitor)
-    }
-}
-
-/// Custom deserialization module for groups
-mod groups {
-    use serde::Deserialize;
-    use serde::Deserializer;
-
-    use super::ParsedGroups;
-
-    /// A marker object for overriding `newlinesBetween` at a specific group boundary.
-    #[derive(Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    struct NewlinesBetweenMarker {
-        newlines_between: bool,
-    }
-
-    /// Intermediate representation for group items.
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum GroupItem {
-        /// A `{ "newlinesBetween": bool }` marker object.
-        NewlinesBetween(NewlinesBetweenMarker),
-        /// A single group name string (e.g. `"value-builtin"`).
-        Single(String),
-        /// Multiple group names treated as one group (e.g. `["value-builtin", "value-external"]`).
-        Multiple(Vec<String>),
-    }
-
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<ParsedGroups, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let items: Vec<GroupItem> = Vec::deserialize(deserializer)?;
-
-        let mut groups: Vec<Vec<oxc_formatter::GroupEntry>> = Vec::new();
-        let mut newline_boundary_overrides: Vec<Option<bool>> = Vec::new();
-        let mut pending_override: Option<bool> = None;
-
-        for item in items {
-            match item {
-                GroupItem::NewlinesBetween(marker) => {
-                    pending_override = Some(marker.newlines_between);
-                }
-                other => {
-                    if !groups.is_empty() {
-                        newline_boundary_overrides.push(pending_override.take());
-                    }
-                    let entries = match other {
-                        GroupItem::Single(s) => {
-                            vec![oxc_formatter::GroupEntry::parse(&s)]
-                        }
-                        GroupItem::Multiple(v) => {
-                            v.into_iter().map(|s| oxc_formatter::GroupEntry::parse(&s)).collect()
-                        }
-                        GroupItem::NewlinesBetween(_) => unreachable!(),
-                    };
-                    groups.push(entries);
-                }
-            }
-        }
-
-        Ok(ParsedGroups { groups, newline_boundary_overrides })
-    }
-}
@@ -0,0 +1,127 @@
+//! Checks that formatting keeps the syntax tree of scripts.
+
+use common::{SourceError, TreeToken, compare_trees};
+use oxc_allocator::Allocator;
+use oxc_ast::AstKind;
+use oxc_ast::ast::{JSXExpression, JSXExpressionContainer, Program};
+use oxc_ast_visit::{Visit, walk};
+use oxc_formatter::get_parse_options;
+use oxc_span::{GetSpan, SourceType};
+
+use crate::{FormatScript, OxFmtOptions, parse};
+
+/// Checks that `code`, the formatted version of `src`, parses to the same syntax tree.
+///
+/// Parentheses, empty statements, quotes and the spelling of numbers and property keys, and the
+/// layout of JSX text are ignored. When `config` sorts imports, the source is compared with its
+/// imports sorted.
+pub fn verify_script_equivalence(
+    src: &str,
+    code: &str,
+    filename: &str,
+    config: &OxFmtOptions,
+) -> Result<(), SourceError> {
+    let source_type = SourceType::from_path(filename).map_err(|e| e.to_string())?;
+
+    let sorted;
+    let mut src = src;
+    if config.sorts_imports()
+        && let Some(printed) =
+            FormatScript::new(src, filename).config(config.clone())?.organize_imports()?
+        && let Some(range) = printed.range
+    {
+        let (start, end) = (range.start as usize, range.end as usize);
+        sorted = format!("{}{}{}", &src[..start], printed.code, &src[end..]);
+        src = &sorted;
+    }
+
+    let allocator = Allocator::new();
+    let before = parse(&allocator, src, source_type, get_parse_options())?;
+    let after = parse(&allocator, code, source_type, get_parse_options())
+        .map_err(|_| "the formatted script has syntax errors".to_string())?;
+
+    compare_trees("script", src, &tree_tokens(&before.program), code, &tree_tokens(&after.program))
+}
+
+/// Flattens `program` to its node kinds and normalized values, in source order.
+fn tree_tokens(program: &Program) -> Vec<TreeToken> {
+    let mut visitor = TreeTokens { tokens: vec![] };
+    visitor.visit_program(program);
+    visitor.tokens
+}
+
+struct TreeTokens {
+    tokens: Vec<TreeToken>,
+}
+
+impl<'a> Visit<'a> for TreeTokens {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        let offset = kind.span().start;
+        let text = match kind {
+            // Nodes the formatter may add or remove around the same content.
+            AstKind::ParenthesizedExpression(_) | AstKind::EmptyStatement(_) => return,
+            // Property keys may be quoted or not.
+            AstKind::IdentifierName(it) => format!("{:?}", it.name.as_str()),
+            AstKind::StringLiteral(it) => format!("{:?}", it.value.as_str()),
+            AstKind::IdentifierReference(it) => it.name.to_string(),
+            AstKind::BindingIdentifier(it) => it.name.to_string(),
+            AstKind::LabelIdentifier(it) => it.name.to_string(),
+            AstKind::PrivateIdentifier(it) => format!("#{}", it.name),
+            AstKind::NumericLiteral(it) => it.value.to_string(),
+            AstKind::BigIntLiteral(it) => format!("{}n", it.value),
+            AstKind::BooleanLiteral(it) => it.value.to_string(),
+            AstKind::RegExpLiteral(it) => it.regex.to_string(),
+            AstKind::TemplateElement(it) => format!("`{}`", it.value.raw),
+            AstKind::JSXIdentifier(it) => it.name.to_string(),
+            AstKind::JSXText(it) => {
+                self.tokens.extend(it.value.split_whitespace().map(|w| TreeToken::new(w, offset)));
+                return;
+            }
+            AstKind::VariableDeclaration(it) => it.kind.as_str().to_string(),
+            AstKind::BinaryExpression(it) => it.operator.as_str().to_string(),
+            AstKind::LogicalExpression(it) => it.operator.as_str().to_string(),
+            AstKind::AssignmentExpression(it) => it.operator.as_str().to_string(),
+            AstKind::UnaryExpression(it) => it.operator.as_str().to_string(),
+            AstKind::UpdateExpression(it) => {
+                let operator = it.operator.as_str();
+                if it.prefix { format!("{operator}_") } else { format!("_{operator}") }
+            }
+            _ => format!("{:?}", kind.ty()),
+        };
+
+        self.tokens.push(TreeToken::new(text, offset));
+    }
+
+    fn visit_jsx_expression_container(&mut self, it: &JSXExpressionContainer<'a>) {
+        // `{" "}` between JSX children only spells out whitespace.
+        if let JSXExpression::StringLiteral(literal) = &it.expression
+            && literal.value.trim().is_empty()
+        {
+            return;
+        }
+        walk::walk_jsx_expression_container(self, it);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_unchanged_trees() {
+        let src = "const a = (1.0), b = 'x';;\nlet f = x => <p>a  {\" \"}\n b</p>;\n";
+        let code = "const a = 1.0,\n  b = \"x\";\nlet f = (x) => <p>a b</p>;\n";
+        assert_eq!(verify_script_equivalence(src, code, "a.jsx", &OxFmtOptions::default()), Ok(()));
+    }
+
+    #[test]
+    fn reports_changed_literals() {
+        let err =
+            verify_script_equivalence("a = 'x';", "a = \"y\";\n", "a.js", &OxFmtOptions::default())
+                .unwrap_err();
+        assert!(
+            err.message.contains(r#"`"x"` at 1:5 of the source became `"y"` at 1:5 of the output"#),
+            "{err}"
+        );
+    }
+}
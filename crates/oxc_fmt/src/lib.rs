@@ -1,22 +1,36 @@
+#[cfg(feature = "component")]
+mod component;
 mod config;
+mod equivalence;
+mod organize_imports;
+mod range;
 
+pub use common::{
+    CheckResult, FormattedWithCursor, PrintedRange, SourceError, TextEdit, TextRange,
+};
+pub use config::OxFmtOptions;
+pub use equivalence::verify_script_equivalence;
 pub use oxc_formatter::{EmbeddedFormatterCallback, ExternalCallbacks};
 
 use oxc_allocator::Allocator;
+use oxc_ast::ast::Program;
 use oxc_formatter::{FormatOptions, Formatter, get_parse_options};
-use oxc_parser::Parser;
+use oxc_parser::{ParseOptions, Parser, ParserReturn};
 use oxc_span::SourceType;
 
+#[cfg(feature = "wasm-bindgen")]
+use common::{EncodedRange, OffsetConverter, OffsetEncoding, Position};
 #[cfg(feature = "wasm-bindgen")]
 use wasm_bindgen::prelude::*;
 
-use crate::config::OxFmtOptions;
-
 #[cfg(feature = "wasm-bindgen")]
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "Config")]
     pub type Config;
+
+    #[wasm_bindgen(typescript_type = "TextRange | LineColumnRange")]
+    pub type JsTextRange;
 }
 
 #[cfg(feature = "wasm-bindgen")]
@@ -27,6 +41,19 @@ type Lang = "j" | "t";
 type X = "" | "x";
 
 type Filename = `index.${Mod}${Lang}s${X}` | `index.d.${Mod}ts${X}` | (string & {});
+
+import type {
+	CheckResult,
+	FormattedWithCursor,
+	LineColumn,
+	LineColumnRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./text.d.ts";
+
+export type * from "./text.d.ts";
 "#;
 
 /// Formats the given JavaScript/TypeScript code with the provided Configuration.
@@ -50,6 +77,152 @@ pub fn format_script(
     FormatScript::new(code, filename).config(config)?.format()
 }
 
+/// Formats a range of the given JavaScript/TypeScript code with the provided Configuration.
+/// Only the smallest statement list enclosing the range is formatted.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatRange, unchecked_return_type = "PrintedRange")]
+pub fn format_range(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to format")] code: &str,
+    #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(code, encoding.unwrap_or_default());
+    let range: EncodedRange =
+        serde_wasm_bindgen::from_value(range.into()).map_err(|e| e.to_string())?;
+    let range = offsets.range_to_utf8(range)?;
+
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let printed = FormatScript::new(code, filename).config(config)?.range(range).format_range()?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_printed(printed)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given JavaScript/TypeScript code, returning where the cursor ends up in the output.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatWithCursor, unchecked_return_type = "FormattedWithCursor")]
+pub fn format_with_cursor(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to format")] code: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "number | LineColumn",
+        param_description = "The cursor position, counted as given by `encoding`"
+    )]
+    cursor_offset: JsValue,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the cursor positions are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(code, encoding.unwrap_or_default());
+    let cursor_offset: Position =
+        serde_wasm_bindgen::from_value(cursor_offset).map_err(|e| e.to_string())?;
+    let cursor_offset = offsets.to_utf8(cursor_offset)?;
+
+    let formatted =
+        FormatScript::new(code, filename).config(config)?.format_with_cursor(cursor_offset)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_cursor(formatted)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given JavaScript/TypeScript code, returning the edits that turn it into the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = formatEdits, unchecked_return_type = "TextEdit[]")]
+pub fn format_edits(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to format")] code: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let edits = FormatScript::new(code, filename).config(config)?.format_edits()?;
+
+    serde_wasm_bindgen::to_value(&edits).map_err(|e| e.to_string())
+}
+
+/// Checks whether the given JavaScript/TypeScript code is already formatted, without returning the formatted code.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(unchecked_return_type = "CheckResult")]
+pub fn check(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to check")] code: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let result = FormatScript::new(code, filename).config(config)?.check()?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
+}
+
+/// Sorts the import statements of the given JavaScript/TypeScript code without formatting the rest of it.
+/// Returns the replaced source range and its new text, or `undefined` when the imports are already sorted.
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(js_name = organizeImports, unchecked_return_type = "PrintedRange | undefined")]
+pub fn organize_imports(
+    #[wasm_bindgen(param_description = "The JavaScript/TypeScript code to organize")] code: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "Filename",
+        param_description = "The filename to determine the source type (e.g., .js, .ts, .jsx, .tsx)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config")] config: Option<Config>,
+) -> Result<JsValue, String> {
+    let config = config
+        .map(|x| serde_wasm_bindgen::from_value(x.clone()))
+        .transpose()
+        .map_err(|op| op.to_string())?
+        .unwrap_or_default();
+
+    let printed = FormatScript::new(code, filename).config(config)?.organize_imports()?;
+
+    serde_wasm_bindgen::to_value(&printed).map_err(|e| e.to_string())
+}
+
 /// Builder for formatting JavaScript/TypeScript code.
 ///
 /// # Example
@@ -58,7 +231,8 @@ pub fn format_script(
 ///     .config(config)  // required: format configuration
 ///     .ext("ts")  // optional: override extension for source type
 ///     .embedded(formatter)  // optional: embedded language formatter
-///     .format()
+///     .range(range)  // optional: the range used by `.format_range()`
+///     .format()  // or `.format_with_cursor(offset)`, or `.organize_imports()` to only sort the import block
 /// ```
 pub struct FormatScript<'a> {
     src: &'a str,
@@ -66,11 +240,12 @@ pub struct FormatScript<'a> {
     ext: Option<&'a str>,
     config: Option<FormatOptions>,
     external_callbacks: Option<ExternalCallbacks>,
+    range: Option<TextRange>,
 }
 
 impl<'a> FormatScript<'a> {
     pub fn new(src: &'a str, filename: &'a str) -> Self {
-        Self { src, filename, ext: None, config: None, external_callbacks: None }
+        Self { src, filename, ext: None, config: None, external_callbacks: None, range: None }
     }
 
     /// Set format configuration.
@@ -93,31 +268,174 @@ impl<'a> FormatScript<'a> {
         self
     }
 
+    /// Set the range formatted by [`FormatScript::format_range`], in UTF-8 byte offsets.
+    pub fn range(mut self, range: TextRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+
     pub fn format(self) -> Result<String, String> {
-        let source_type = match self.ext {
-            Some(ext) => SourceType::from_path(format!("_.{ext}")).map_err(|e| e.to_string())?,
-            None => SourceType::from_path(self.filename).map_err(|e| e.to_string())?,
+        let source_type = self.source_type()?;
+
+        let allocator = Allocator::new();
+        let ret = parse(&allocator, self.src, source_type, get_parse_options())?;
+
+        let options = self.config.unwrap_or_default();
+
+        format_program(&allocator, &ret.program, options, self.external_callbacks)
+    }
+
+    /// Format the whole program and map `cursor_offset` to where the cursor ends up in the output.
+    pub fn format_with_cursor(self, cursor_offset: u32) -> Result<FormattedWithCursor, String> {
+        let src = self.src;
+        FormattedWithCursor::check_cursor(src, cursor_offset)?;
+
+        let code = self.format()?;
+
+        Ok(FormattedWithCursor::from_diff(src, code, cursor_offset))
+    }
+
+    /// Format the whole program, returning the minimal edits that turn it into the formatted code.
+    pub fn format_edits(self) -> Result<Vec<TextEdit>, String> {
+        let src = self.src;
+        let code = self.format()?;
+
+        Ok(common::text_edits(src, &code))
+    }
+
+    /// Check whether the program is already formatted, reporting where it first differs otherwise.
+    pub fn check(self) -> Result<CheckResult, String> {
+        let src = self.src;
+        let code = self.format()?;
+
+        Ok(CheckResult::new(src, &code))
+    }
+
+    /// Format the smallest statement list enclosing the configured range.
+    ///
+    /// Returns the replaced source range and its new text, indented by the blocks the statements
+    /// are nested in. The whole program is formatted when no range is configured.
+    /// Fails when the enclosing statements can't be parsed on their own, e.g. because they contain
+    /// `yield` or labeled jumps.
+    pub fn format_range(self) -> Result<PrintedRange, String> {
+        let source_type = self.source_type()?;
+
+        let allocator = Allocator::new();
+        let ret = parse(&allocator, self.src, source_type, get_parse_options())?;
+
+        let options = self.config.unwrap_or_default();
+
+        let Some(range) = self.range else {
+            let code = format_program(&allocator, &ret.program, options, self.external_callbacks)?;
+            return Ok(PrintedRange { code, range: Some(TextRange::whole(self.src)) });
         };
+        range.check(self.src)?;
+
+        let Some(enclosing) = range::enclosing_statements(&ret.program, range) else {
+            return Ok(PrintedRange { code: String::new(), range: None });
+        };
+        let (start, end) = (enclosing.span.start as usize, enclosing.span.end as usize);
+        let depth = enclosing.depth;
+
+        // Nest the statements in as many blocks as they are in,
+        // so that they are printed at their indentation and line width.
+        let wrapped =
+            format!("{}{}{}", "{".repeat(depth), &self.src[start..end], "}".repeat(depth));
+
+        let range_allocator = Allocator::new();
+        let parse_options =
+            ParseOptions { allow_return_outside_function: true, ..get_parse_options() };
+        let range_ret = parse(&range_allocator, &wrapped, source_type, parse_options)
+            .map_err(|e| format!("the statements around the range can't be formatted: {e}"))?;
+
+        let formatted =
+            format_program(&range_allocator, &range_ret.program, options, self.external_callbacks)?;
+
+        let lines: Vec<_> = formatted.trim_end().split('\n').collect();
+        let inner = lines.get(depth..lines.len().saturating_sub(depth)).unwrap_or_default();
+        let code = inner.join("\n");
+
+        // Replace the indentation of the first line too when the statements start their line.
+        let line_start = self.src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let (start, code) = if self.src[line_start..start].trim().is_empty() {
+            (line_start, code.trim_end())
+        } else {
+            (start, code.trim())
+        };
+
+        Ok(PrintedRange {
+            code: code.to_string(),
+            range: Some(TextRange { start: start as u32, end: end as u32 }),
+        })
+    }
+
+    /// Apply only the `sort_imports` transformation to the leading import block.
+    ///
+    /// The imports are moved with their source text as it is, along with the comments on their
+    /// own lines before them and on their line after them, while the comments before the first
+    /// import stay in place. Everything outside the import block is left untouched.
+    /// Returns the replaced source range and its new text, or `None` when the imports are already sorted.
+    /// Sorting uses the default `sortImports` options when none are configured.
+    pub fn organize_imports(self) -> Result<Option<PrintedRange>, String> {
+        let source_type = self.source_type()?;
 
         let allocator = Allocator::new();
-        let ret = Parser::new(&allocator, self.src, source_type)
-            .with_options(get_parse_options())
-            .parse();
-
-        if !ret.errors.is_empty() {
-            return Err(ret
-                .errors
-                .into_iter()
-                .map(|e| e.to_string())
-                .collect::<Vec<_>>()
-                .join("\n"));
+        let ret = parse(&allocator, self.src, source_type, get_parse_options())?;
+
+        let Some(block) = organize_imports::import_block(self.src, &ret.program) else {
+            return Ok(None);
+        };
+        let import_block = &self.src[block.start as usize..block.end as usize];
+
+        let mut options = self.config.unwrap_or_default();
+        if options.sort_imports.is_none() {
+            options.sort_imports =
+                Some(config::sort_imports_options(common::SortImportsOptions::default())?);
         }
 
-        let options = self.config.unwrap_or_default();
+        // The formatter only tells the order of the imports, their text is taken from the source.
+        let block_allocator = Allocator::new();
+        let block_ret = parse(&block_allocator, import_block, source_type, get_parse_options())?;
+        let sorted = format_program(&block_allocator, &block_ret.program, options, None)?;
+
+        let sorted_allocator = Allocator::new();
+        let sorted_ret = parse(&sorted_allocator, &sorted, source_type, get_parse_options())?;
+
+        organize_imports::splice_sorted(self.src, &ret.program, &sorted, &sorted_ret.program)
+    }
+
+    fn source_type(&self) -> Result<SourceType, String> {
+        match self.ext {
+            Some(ext) => SourceType::from_path(format!("_.{ext}")).map_err(|e| e.to_string()),
+            None => SourceType::from_path(self.filename).map_err(|e| e.to_string()),
+        }
+    }
+}
 
-        let formatted = Formatter::new(&allocator, options)
-            .format_with_external_callbacks(&ret.program, self.external_callbacks);
+fn format_program(
+    allocator: &Allocator,
+    program: &Program,
+    options: FormatOptions,
+    external_callbacks: Option<ExternalCallbacks>,
+) -> Result<String, String> {
+    Formatter::new(allocator, options)
+        .format_with_external_callbacks(program, external_callbacks)
+        .print()
+        .map(|p| p.into_code())
+        .map_err(|e| e.to_string())
+}
+
+fn parse<'a>(
+    allocator: &'a Allocator,
+    src: &'a str,
+    source_type: SourceType,
+    options: ParseOptions,
+) -> Result<ParserReturn<'a>, String> {
+    let ret = Parser::new(allocator, src, source_type).with_options(options).parse();
 
-        formatted.print().map(|p| p.into_code()).map_err(|e| e.to_string())
+    if !ret.errors.is_empty() {
+        return Err(ret.errors.into_iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"));
     }
+
+    Ok(ret)
 }
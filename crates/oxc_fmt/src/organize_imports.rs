@@ -0,0 +1,269 @@
+use std::collections::{HashMap, VecDeque};
+
+use oxc_ast::ast::{ImportDeclaration, Program, Statement};
+
+use crate::{PrintedRange, TextRange};
+
+/// An import of the leading import block, with the comments that move along with it.
+struct Chunk {
+    /// Start of the own-line comments before the import, or of the import itself for the first
+    /// import, so that a file header stays in place.
+    start: usize,
+    /// End of the import, or of the comments following it on the same line.
+    end: usize,
+}
+
+/// The leading run of import declarations of a program.
+pub(crate) fn leading_imports<'b, 'a>(program: &'b Program<'a>) -> Vec<&'b ImportDeclaration<'a>> {
+    program
+        .body
+        .iter()
+        .skip_while(|stmt| !matches!(stmt, Statement::ImportDeclaration(_)))
+        .map_while(|stmt| match stmt {
+            Statement::ImportDeclaration(decl) => Some(&**decl),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The source range of the leading import block, including the comments on the line of its
+/// last import. Returns `None` when the program has no imports.
+pub(crate) fn import_block(src: &str, program: &Program) -> Option<TextRange> {
+    let chunks = chunks(src, program);
+    let (first, last) = (chunks.first()?, chunks.last()?);
+
+    Some(TextRange { start: first.start as u32, end: last.end as u32 })
+}
+
+/// Moves the imports of the leading import block of `src` into the order of `sorted`,
+/// the import block sorted by the formatter, keeping the source text of every import
+/// and of the comments attached to it.
+///
+/// Returns `None` when the imports are already in order.
+pub(crate) fn splice_sorted(
+    src: &str,
+    program: &Program,
+    sorted: &str,
+    sorted_program: &Program,
+) -> Result<Option<PrintedRange>, String> {
+    let imports = leading_imports(program);
+    let chunks = chunks(src, program);
+    let (Some(first), Some(last)) = (chunks.first(), chunks.last()) else {
+        return Ok(None);
+    };
+
+    let mut positions: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (index, decl) in imports.iter().enumerate() {
+        positions.entry(import_key(decl)).or_default().push_back(index);
+    }
+
+    let sorted_imports = leading_imports(sorted_program);
+    if sorted_imports.len() != imports.len() {
+        return Err("sorting the imports changed their number".into());
+    }
+
+    let mut order = Vec::with_capacity(imports.len());
+    for (k, decl) in sorted_imports.iter().enumerate() {
+        let index = positions
+            .get_mut(&import_key(decl))
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| format!("sorting the imports changed `{}`", decl.source.value))?;
+
+        let blank_line_before = k > 0 && {
+            let gap = &sorted[sorted_imports[k - 1].span.end as usize..decl.span.start as usize];
+            let lines: Vec<_> = gap.split('\n').collect();
+            lines.len() > 2 && lines[1..lines.len() - 1].iter().any(|line| line.trim().is_empty())
+        };
+
+        order.push((index, blank_line_before));
+    }
+
+    let newline = if src.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut code = String::with_capacity(last.end - first.start);
+
+    for (k, &(index, blank_line_before)) in order.iter().enumerate() {
+        if k > 0 {
+            let previous = order[k - 1].0;
+            let gap =
+                (index == previous + 1).then(|| &src[chunks[previous].end..chunks[index].start]);
+
+            // Keep the whitespace between imports that stay next to each other.
+            if let Some(gap) =
+                gap.filter(|gap| (gap.matches('\n').count() > 1) == blank_line_before)
+            {
+                code.push_str(gap);
+            } else {
+                code.push_str(newline);
+                if blank_line_before {
+                    code.push_str(newline);
+                }
+            }
+        }
+        code.push_str(&src[chunks[index].start..chunks[index].end]);
+    }
+
+    if code == src[first.start..last.end] {
+        return Ok(None);
+    }
+
+    Ok(Some(PrintedRange {
+        code,
+        range: Some(TextRange { start: first.start as u32, end: last.end as u32 }),
+    }))
+}
+
+/// Identifies an import across the source and its sorted copy: by the names it binds,
+/// which are unique in a module, or by its source for imports binding no names.
+fn import_key(decl: &ImportDeclaration) -> String {
+    match decl.specifiers.as_ref().filter(|specifiers| !specifiers.is_empty()) {
+        Some(specifiers) => {
+            let mut names: Vec<_> =
+                specifiers.iter().map(|specifier| specifier.local().name.as_str()).collect();
+            names.sort_unstable();
+            names.join(",")
+        }
+        None => format!("\"{}\"", decl.source.value),
+    }
+}
+
+fn chunks(src: &str, program: &Program) -> Vec<Chunk> {
+    let mut chunks: Vec<Chunk> = vec![];
+
+    for decl in leading_imports(program) {
+        let (start, end) = (decl.span.start as usize, decl.span.end as usize);
+
+        // Own-line comments between two imports move with the import after them.
+        let start = match chunks.last() {
+            Some(previous) => {
+                let gap = &src[previous.end..start];
+                previous.end + (gap.len() - gap.trim_start().len())
+            }
+            None => start,
+        };
+
+        chunks.push(Chunk { start, end: same_line_comments_end(src, program, end) });
+    }
+
+    chunks
+}
+
+/// End of the comments following `end` on the same line, or `end` without any.
+fn same_line_comments_end(src: &str, program: &Program, import_end: usize) -> usize {
+    let mut end = import_end;
+    let comments =
+        program.comments.iter().skip_while(|comment| comment.span.start < import_end as u32);
+
+    for comment in comments {
+        let gap = &src[end..comment.span.start as usize];
+        if !gap.trim().is_empty() || gap.contains('\n') {
+            break;
+        }
+        end = comment.span.end as usize;
+    }
+
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    use super::*;
+    use crate::{FormatScript, OxFmtOptions};
+
+    fn organize(src: &str) -> Option<PrintedRange> {
+        FormatScript::new(src, "index.ts")
+            .config(OxFmtOptions::default())
+            .unwrap()
+            .organize_imports()
+            .unwrap()
+    }
+
+    fn splice(src: &str, sorted: &str) -> Option<String> {
+        let allocator = Allocator::new();
+        let source_type = SourceType::ts();
+        let program = Parser::new(&allocator, src, source_type).parse().program;
+        let sorted_program = Parser::new(&allocator, sorted, source_type).parse().program;
+
+        let printed = splice_sorted(src, &program, sorted, &sorted_program).unwrap()?;
+        let range = printed.range.unwrap();
+
+        Some(format!(
+            "{}{}{}",
+            &src[..range.start as usize],
+            printed.code,
+            &src[range.end as usize..]
+        ))
+    }
+
+    #[test]
+    fn moves_comments_with_their_imports() {
+        let src = r#"// @ts-check
+import b from './b' // the b
+// about a
+import {  x } from 'a';
+let y = x;
+"#;
+        let sorted = r#"// about a
+import { x } from "a";
+
+import b from "./b"; // the b
+"#;
+
+        assert_eq!(
+            splice(src, sorted).unwrap(),
+            r#"// @ts-check
+// about a
+import {  x } from 'a';
+
+import b from './b' // the b
+let y = x;
+"#
+        );
+    }
+
+    #[test]
+    fn keeps_sorted_imports_as_they_are() {
+        let src = "import {  x } from 'a'\n\n\nimport b from './b' /* b */ /* c */\n";
+        let sorted = "import { x } from \"a\";\n\nimport b from \"./b\"; /* b */ /* c */\n";
+
+        assert_eq!(splice(src, sorted), None);
+    }
+
+    #[test]
+    fn keeps_side_effect_imports_in_order() {
+        let src = "import 'z';\nimport 'y';\nimport a from 'a';\n";
+        let sorted = "import \"z\";\nimport \"y\";\nimport a from \"a\";\n";
+
+        assert_eq!(splice(src, sorted), None);
+    }
+
+    #[test]
+    fn organizes_only_the_import_block() {
+        let src =
+            "\"use client\"\n\nimport b from './b' // the b\nimport a from 'a'\nlet  y = a+b\n";
+
+        let printed = organize(src).unwrap();
+        assert_eq!(printed.code, "import a from 'a'\n\nimport b from './b' // the b");
+        assert_eq!(printed.range.map(|range| (range.start, range.end)), Some((14, 60)));
+    }
+
+    #[test]
+    fn leaves_sorted_imports_alone() {
+        assert!(
+            organize("import a from 'a'\n\nimport b from './b' // the b\nlet  y = a+b\n").is_none()
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let result = FormatScript::new("import a from 'a'\nlet = ;", "index.ts")
+            .config(OxFmtOptions::default())
+            .unwrap()
+            .organize_imports();
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use oxc_allocator::Vec as ArenaVec;
+use oxc_ast::ast::{
+    ArrowFunctionExpression, BlockStatement, ClassBody, FunctionBody, ObjectExpression, Program,
+    Statement, StaticBlock, SwitchCase, SwitchStatement, TSModuleBlock,
+};
+use oxc_ast_visit::{Visit, walk};
+use oxc_span::{GetSpan, Span};
+
+use crate::TextRange;
+
+/// Statements found by [`enclosing_statements`], with the number of indented blocks they are in.
+pub(crate) struct Enclosing {
+    pub span: Span,
+    pub depth: usize,
+}
+
+/// Finds the smallest run of sibling statements covering `range`.
+///
+/// Falls back to the top-level statements touched by `range` when no statement list covers it,
+/// e.g. when the range starts or ends between statements. Returns `None` when no statement is touched.
+pub(crate) fn enclosing_statements(program: &Program, range: TextRange) -> Option<Enclosing> {
+    let mut visitor = EnclosingStatements { range, depth: 0, best: None };
+    visitor.visit_program(program);
+
+    visitor.best.or_else(|| {
+        touched_statements(&program.body, range).map(|span| Enclosing { span, depth: 0 })
+    })
+}
+
+struct EnclosingStatements {
+    range: TextRange,
+    /// The number of blocks the formatter indents around the visited statements.
+    depth: usize,
+    best: Option<Enclosing>,
+}
+
+impl EnclosingStatements {
+    fn indented(&mut self, walk: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        walk(self);
+        self.depth -= 1;
+    }
+}
+
+impl<'a> Visit<'a> for EnclosingStatements {
+    fn visit_statements(&mut self, statements: &ArenaVec<'a, Statement<'a>>) {
+        if let Some(span) = touched_statements(statements, self.range)
+            && span.start <= self.range.start
+            && self.range.end <= span.end
+            && self.best.as_ref().is_none_or(|best| span.size() <= best.span.size())
+        {
+            self.best = Some(Enclosing { span, depth: self.depth });
+        }
+
+        walk::walk_statements(self, statements);
+    }
+
+    fn visit_arrow_function_expression(&mut self, it: &ArrowFunctionExpression<'a>) {
+        // The expression body of an arrow function is no statement that could be formatted.
+        if let Some(expression) = it.get_expression() {
+            self.visit_formal_parameters(&it.params);
+            self.visit_expression(expression);
+        } else {
+            walk::walk_arrow_function_expression(self, it);
+        }
+    }
+
+    fn visit_block_statement(&mut self, it: &BlockStatement<'a>) {
+        self.indented(|visitor| walk::walk_block_statement(visitor, it));
+    }
+
+    fn visit_function_body(&mut self, it: &FunctionBody<'a>) {
+        self.indented(|visitor| walk::walk_function_body(visitor, it));
+    }
+
+    // Methods are indented within their class or object literal, and their bodies within them.
+    fn visit_class_body(&mut self, it: &ClassBody<'a>) {
+        self.indented(|visitor| walk::walk_class_body(visitor, it));
+    }
+
+    fn visit_object_expression(&mut self, it: &ObjectExpression<'a>) {
+        self.indented(|visitor| walk::walk_object_expression(visitor, it));
+    }
+
+    fn visit_static_block(&mut self, it: &StaticBlock<'a>) {
+        self.indented(|visitor| walk::walk_static_block(visitor, it));
+    }
+
+    // The cases are indented within the switch, and their statements within the cases.
+    fn visit_switch_statement(&mut self, it: &SwitchStatement<'a>) {
+        self.indented(|visitor| walk::walk_switch_statement(visitor, it));
+    }
+
+    fn visit_switch_case(&mut self, it: &SwitchCase<'a>) {
+        self.indented(|visitor| walk::walk_switch_case(visitor, it));
+    }
+
+    fn visit_ts_module_block(&mut self, it: &TSModuleBlock<'a>) {
+        self.indented(|visitor| walk::walk_ts_module_block(visitor, it));
+    }
+}
+
+fn touched_statements(statements: &[Statement], range: TextRange) -> Option<Span> {
+    let mut touched = statements.iter().map(GetSpan::span).filter(|span| {
+        if range.start == range.end {
+            span.start <= range.start && range.start <= span.end
+        } else {
+            span.start < range.end && range.start < span.end
+        }
+    });
+
+    let first = touched.next()?;
+    let last = touched.next_back().unwrap_or(first);
+
+    Some(Span::new(first.start, last.end))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FormatScript, OxFmtOptions, PrintedRange, TextRange};
+
+    fn format_range(src: &str, offset: usize) -> Result<PrintedRange, String> {
+        let offset = offset as u32;
+        FormatScript::new(src, "index.ts")
+            .config(OxFmtOptions::default())?
+            .range(TextRange { start: offset, end: offset })
+            .format_range()
+    }
+
+    #[test]
+    fn indents_by_the_enclosing_blocks() {
+        let src =
+            "function f(a) {\n    switch (a) {\n        case 1:\n            g( a,1 )\n    }\n}\n";
+        let offset = src.find("g(").unwrap();
+
+        let printed = format_range(src, offset).unwrap();
+        assert_eq!(printed.code, "      g(a, 1);");
+        let range = printed.range.unwrap();
+        assert_eq!(&src[range.start as usize..range.end as usize], "            g( a,1 )");
+    }
+
+    #[test]
+    fn indents_by_the_enclosing_class_and_object_literal() {
+        let src = "class A {\n    m() {\n        g( a,1 )\n    }\n}\n";
+        let printed = format_range(src, src.find("g(").unwrap()).unwrap();
+        assert_eq!(printed.code, "    g(a, 1);");
+
+        let src = "const o = {\n    m() {\n        g( a,1 )\n    },\n};\n";
+        let printed = format_range(src, src.find("g(").unwrap()).unwrap();
+        assert_eq!(printed.code, "    g(a, 1);");
+        let range = printed.range.unwrap();
+        assert_eq!(&src[range.start as usize..range.end as usize], "        g( a,1 )");
+    }
+
+    #[test]
+    fn skips_ranges_between_statements() {
+        let printed = format_range("a;\n\n\nb;\n", 4).unwrap();
+        assert_eq!(printed.code, "");
+        assert!(printed.range.is_none());
+    }
+
+    #[test]
+    fn reports_statements_that_cannot_stand_alone() {
+        let src = "function* g() {\n  yield 1;\n}\n";
+        assert!(format_range(src, src.find("yield").unwrap()).is_err());
+    }
+}
@@ -5,6 +5,11 @@ import type { Config as StyleConfig } from "./malva_options.d.ts";
 import type { Config as JsonConfig } from "./json_options.d.ts";
 import type { Config as GraphqlConfig } from "./graphql_options.d.ts";
 
+/**
+ * A check run on the formatter output.
+ */
+export type Verify = "idempotent" | "equivalent";
+
 /**
  * Main configuration interface for Web formatter.
  */
@@ -18,9 +23,19 @@ export interface Config extends LayoutConfig {
 	/** Style (CSS/SCSS/SASS/LESS) formatter configuration. */
 	style?: StyleConfig;
 
-	/** JSON formatter configuration (uses LayoutConfig only). */
+	/** JSON formatter configuration. */
 	json?: JsonConfig;
 
 	/** GraphQL formatter configuration. */
 	graphql?: GraphqlConfig;
+
+	/**
+	 * Checks run on the output before it is returned, one or a list.
+	 * With `"idempotent"`, the output and every embedded script and style block are formatted a second time,
+	 * and formatting fails with a diff of the two passes when they differ.
+	 * With `"equivalent"`, the output of scripts, styles, JSON and GraphQL, including embedded blocks,
+	 * is parsed again and formatting fails when its syntax tree differs from the one of the source.
+	 * Markup itself is not compared, only its embedded blocks are.
+	 */
+	verify?: Verify | Verify[];
 }
@@ -0,0 +1 @@
+../../common/extra/sort_imports.d.ts
\ No newline at end of file
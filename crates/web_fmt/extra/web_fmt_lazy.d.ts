@@ -0,0 +1,104 @@
+/**
+ * WASM formatter for web development (HTML/CSS/JS/JSON/GraphQL), loading each language on first use.
+ *
+ * A small core module picks the language of a file, and the module of that language is fetched
+ * the first time a file of it is formatted. Markup fetches the modules of its embedded
+ * `<script>` and `<style>` blocks on demand.
+ *
+ * @example
+ * ```ts
+ * import { format } from "@wasm-fmt/web_fmt/lazy";
+ *
+ * const input = "<div>  hello  </div>";
+ * const output = await format(input, "index.html");
+ * ```
+ *
+ * @module
+ */
+import type {
+	BatchFile,
+	BatchResult,
+	CheckResult,
+	Config,
+	DiffOptions,
+	FormattedRanges,
+	FormattedWithCursor,
+	Language,
+	LineColumn,
+	LineColumnRange,
+	LineRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./web_fmt.d.ts";
+
+export type * from "./web_fmt.d.ts";
+
+/**
+ * Loads the modules of the given languages ahead of their first use.
+ */
+export declare function preload(...languages: Language[]): Promise<void[]>;
+
+/**
+ * Formats the given code based on the file extension, once its language is loaded.
+ */
+export declare function format(src: string, filename: string, config?: Config | null): Promise<string>;
+
+/**
+ * Formats a range of the given code based on the file extension, once its language is loaded.
+ */
+export declare function formatRange(
+	src: string,
+	filename: string,
+	range: TextRange | LineColumnRange,
+	config?: Config | null,
+	encoding?: OffsetEncoding | null,
+): Promise<PrintedRange>;
+
+/**
+ * Formats several ranges of the given code in one pass, once its language is loaded.
+ */
+export declare function formatRanges(
+	src: string,
+	filename: string,
+	ranges: Array<TextRange | LineColumnRange | LineRange>,
+	config?: Config | null,
+	encoding?: OffsetEncoding | null,
+): Promise<FormattedRanges>;
+
+/**
+ * Formats the given code, returning where the cursor ends up in the output, once its language is loaded.
+ */
+export declare function formatWithCursor(
+	src: string,
+	filename: string,
+	cursorOffset: number | LineColumn,
+	config?: Config | null,
+	encoding?: OffsetEncoding | null,
+): Promise<FormattedWithCursor>;
+
+/**
+ * Formats the given code, returning the minimal edits that turn it into the formatted code, once its language is loaded.
+ */
+export declare function formatEdits(src: string, filename: string, config?: Config | null): Promise<TextEdit[]>;
+
+/**
+ * Checks whether the given code is already formatted, once its language is loaded.
+ */
+export declare function check(src: string, filename: string, config?: Config | null): Promise<CheckResult>;
+
+/**
+ * Formats the given code, returning a unified diff to its formatted version, once its language is loaded.
+ */
+export declare function formatDiff(
+	src: string,
+	filename: string,
+	config?: Config | null,
+	options?: DiffOptions | null,
+): Promise<string>;
+
+/**
+ * Formats many files, loading the languages they need. Errors are reported per file.
+ */
+export declare function formatBatch(files: BatchFile[], config?: Config | null): Promise<BatchResult[]>;
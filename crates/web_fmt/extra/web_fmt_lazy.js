@@ -0,0 +1,135 @@
+/* @ts-self-types="./web_fmt_lazy.d.ts" */
+import initCore, { languageOf } from "./lazy/core/web_fmt.js";
+
+const loaders = {
+	script: () => import("./lazy/script/web_fmt.js"),
+	style: () => import("./lazy/style/web_fmt.js"),
+	markup: () => import("./lazy/markup/web_fmt.js"),
+	json: () => import("./lazy/json/web_fmt.js"),
+	graphql: () => import("./lazy/graphql/web_fmt.js"),
+};
+
+/** The language modules being loaded, by language. */
+const loading = new Map();
+/** The language modules ready to use, by language. */
+const loaded = new Map();
+
+let core;
+
+/**
+ * The markup call running: its filename and config, and the languages of the embedded blocks
+ * left as they are because their module is not loaded yet.
+ */
+let embedding;
+
+export function preload(...languages) {
+	return Promise.all(languages.map(load));
+}
+
+function load(language) {
+	let module = loading.get(language);
+	if (module === void 0) {
+		const loader = loaders[language];
+		if (loader === void 0) {
+			return Promise.reject(new Error(`unknown language: ${language}`));
+		}
+
+		module = loader().then(async (module) => {
+			await module.default();
+			if (language === "markup") {
+				module.setEmbeddedFormatter(formatEmbedded);
+			}
+			loaded.set(language, module);
+			return module;
+		});
+		// A failed load, e.g. a network error, is retried on next use.
+		module.catch(() => loading.delete(language));
+		loading.set(language, module);
+	}
+	return module;
+}
+
+function formatEmbedded(src, hints) {
+	const language = languageOf(`embedded.${hints.ext}`);
+	const module = loaded.get(language);
+	if (module !== void 0) {
+		return module.formatEmbedded(src, embedding.filename, hints, embedding.config);
+	}
+
+	embedding.missing.add(language);
+	return src;
+}
+
+/**
+ * Calls `call` with the module of the language of `filename`, once loaded.
+ *
+ * Markup is formatted again once the modules of its embedded blocks are loaded,
+ * as they are only known while formatting.
+ */
+async function run(filename, config, call) {
+	await (core ??= initCore());
+	const language = languageOf(filename);
+	const module = await load(language);
+	if (language !== "markup") {
+		return call(module);
+	}
+
+	for (;;) {
+		embedding = { filename, config, missing: new Set() };
+		let result, error, failed = false;
+		try {
+			result = call(module);
+		} catch (e) {
+			failed = true;
+			error = e;
+		}
+		const { missing } = embedding;
+		embedding = void 0;
+
+		if (missing.size === 0) {
+			if (failed) throw error;
+			return result;
+		}
+		await Promise.all(Array.from(missing, load));
+	}
+}
+
+export function format(src, filename, config) {
+	return run(filename, config, (module) => module.format(src, filename, config));
+}
+
+export function formatRange(src, filename, range, config, encoding) {
+	return run(filename, config, (module) => module.formatRange(src, filename, range, config, encoding));
+}
+
+export function formatRanges(src, filename, ranges, config, encoding) {
+	return run(filename, config, (module) => module.formatRanges(src, filename, ranges, config, encoding));
+}
+
+export function formatWithCursor(src, filename, cursorOffset, config, encoding) {
+	return run(filename, config, (module) => module.formatWithCursor(src, filename, cursorOffset, config, encoding));
+}
+
+export function formatEdits(src, filename, config) {
+	return run(filename, config, (module) => module.formatEdits(src, filename, config));
+}
+
+export function check(src, filename, config) {
+	return run(filename, config, (module) => module.check(src, filename, config));
+}
+
+export function formatDiff(src, filename, config, options) {
+	return run(filename, config, (module) => module.formatDiff(src, filename, config, options));
+}
+
+export async function formatBatch(files, config) {
+	const results = [];
+	for (const { filename, source } of files) {
+		try {
+			results.push({ filename, code: await format(source, filename, config) });
+		} catch (error) {
+			results.push({ filename, error: typeof error === "string" ? error : String(error) });
+		}
+	}
+	return results;
+}
@@ -1,6 +1,6 @@
 /* @ts-self-types="./web_fmt_web.d.ts" */
 import * as import_bg from "./web_fmt_bg.js";
-const { __wbg_set_wasm, format, ...wasmImport } = import_bg;
+const { __wbg_set_wasm, format, formatRange, formatRanges, formatWithCursor, formatEdits, check, formatDiff, Formatter, formatBatch, languages, ...wasmImport } = import_bg;
 
 function getImports() {
 	return {
@@ -90,4 +90,4 @@ export default async function initAsync(init_input) {
 	return finalize_init(instance, module);
 }
 
-export { format };
+export { format, formatRange, formatRanges, formatWithCursor, formatEdits, check, formatDiff, Formatter, formatBatch, languages };
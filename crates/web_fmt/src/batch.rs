@@ -0,0 +1,152 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{FormatError, Formatter, JSConfig};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "BatchFile[]")]
+    pub type JsBatchFiles;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const BATCH: &'static str = r#"
+/**
+ * A file to format in a batch.
+ */
+export interface BatchFile {
+	filename: string;
+	source: string;
+}
+
+/**
+ * Result of formatting one file of a batch: the formatted code, or the error of this file alone.
+ */
+export type BatchResult =
+	| { filename: string; code: string; error?: undefined }
+	| { filename: string; code?: undefined; error: string };
+"#;
+
+/// A file to format in a batch.
+#[derive(Deserialize, Clone, Debug)]
+pub struct BatchFile {
+    pub filename: String,
+    pub source: String,
+}
+
+/// Result of formatting one file of a batch.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct BatchResult {
+    pub filename: String,
+    /// The formatted code, when formatting succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// The error of this file, when formatting failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// The parallel batch shares the formatter across threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Formatter>();
+};
+
+impl Formatter {
+    /// Formats every file with the configuration resolved once.
+    ///
+    /// With the `rayon` feature, files are formatted in parallel. Results are in the order of
+    /// `files` either way. A file that fails to format gets its error in its result,
+    /// and the others are still formatted.
+    #[must_use]
+    pub fn format_batch(&self, files: &[BatchFile]) -> Vec<BatchResult> {
+        let results = self.try_format_batch(files);
+
+        files
+            .iter()
+            .zip(results)
+            .map(|(file, result)| {
+                let (code, error) = match result {
+                    Ok(code) => (Some(code), None),
+                    Err(error) => (None, Some(error.message)),
+                };
+                BatchResult { filename: file.filename.clone(), code, error }
+            })
+            .collect()
+    }
+
+    /// Formats every file like [`Formatter::format_batch`], with the [`FormatError`] of each file
+    /// that fails to format.
+    #[must_use]
+    pub fn try_format_batch(&self, files: &[BatchFile]) -> Vec<Result<String, FormatError>> {
+        #[cfg(feature = "rayon")]
+        let files = files.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let files = files.iter();
+
+        files.map(|file| self.try_format(&file.source, &file.filename)).collect()
+    }
+}
+
+/// Formats many files in one call, sharing the resolved configuration across files.
+///
+/// Errors are reported per file, so that one broken file does not abort the batch.
+#[wasm_bindgen(js_name = formatBatch, unchecked_return_type = "BatchResult[]")]
+pub fn format_batch(
+    #[wasm_bindgen(param_description = "The files to format, with their filename and source code")]
+    files: JsBatchFiles,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+) -> Result<JsValue, String> {
+    let files: Vec<BatchFile> =
+        serde_wasm_bindgen::from_value(files.into()).map_err(|e| e.to_string())?;
+    let formatter = Formatter::new(config)?;
+
+    let results = formatter.format_batch(&files);
+
+    serde_wasm_bindgen::to_value(&results).map_err(|e| e.to_string())
+}
+
+// `just test-rust` runs these with and without the `rayon` feature.
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    fn file(filename: String, source: &str) -> BatchFile {
+        BatchFile { filename, source: source.into() }
+    }
+
+    #[test]
+    fn keeps_the_order_of_the_files() {
+        let formatter = Formatter::from_json(&serde_json::json!({})).unwrap();
+        let files: Vec<_> =
+            (0..64).map(|i| file(format!("{i}.json"), &format!("{{ \"a\": {i} }}"))).collect();
+
+        let results = formatter.format_batch(&files);
+
+        assert_eq!(results.len(), files.len());
+        for (file, result) in files.iter().zip(&results) {
+            assert_eq!(result.filename, file.filename);
+            assert_eq!(result.code, Some(formatter.format(&file.source, &file.filename).unwrap()));
+        }
+    }
+
+    #[test]
+    fn reports_failing_files_without_aborting() {
+        let formatter = Formatter::from_json(&serde_json::json!({})).unwrap();
+        let files = [
+            file("a.json".into(), "{ \"a\": 1 }"),
+            file("broken.json".into(), "{ \"a\": bad }"),
+            file("b.json".into(), "{ \"b\": 2 }"),
+        ];
+
+        let results = formatter.format_batch(&files);
+
+        let filenames: Vec<_> = results.iter().map(|result| result.filename.as_str()).collect();
+        assert_eq!(filenames, ["a.json", "broken.json", "b.json"]);
+        assert!(results[0].code.is_some() && results[0].error.is_none());
+        assert!(results[1].code.is_none() && results[1].error.is_some());
+        assert!(results[2].code.is_some() && results[2].error.is_none());
+    }
+}
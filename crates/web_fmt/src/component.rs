@@ -0,0 +1,77 @@
+//! The WebAssembly component, exporting the `web` interface of the WIT world in `wit/`.
+
+use common::component::types;
+use common::{CheckResult, TextRange};
+use serde_json::{Map, Value};
+
+use crate::{LanguageConfigs, format_range_with_configs, format_with_configs, language};
+use exports::wasm_fmt::formatter::web::WebConfig;
+
+wit_bindgen::generate!({
+    world: "web-formatter",
+    path: "../../wit",
+    with: { "wasm-fmt:formatter/types": common::component::types },
+});
+
+struct Component;
+
+/// Resolves the configuration through its JSON form, the shape of the JavaScript config.
+fn resolve(config: Option<&WebConfig>) -> Result<LanguageConfigs, String> {
+    let mut json = Map::new();
+
+    if let Some(config) = config {
+        config.layout.write_json(&mut json);
+
+        let languages = [
+            ("script", &config.script),
+            ("style", &config.style),
+            ("markup", &config.markup),
+            ("json", &config.json),
+            ("graphql", &config.graphql),
+        ];
+        for (language, config) in languages {
+            if let Some(config) = config {
+                json.insert(language.into(), config.to_json()?);
+            }
+        }
+    }
+
+    LanguageConfigs::resolve_json(&Value::Object(json))
+}
+
+impl exports::wasm_fmt::formatter::web::Guest for Component {
+    fn languages() -> Vec<String> {
+        language::compiled_languages()
+    }
+
+    fn format(src: String, filename: String, config: Option<WebConfig>) -> Result<String, String> {
+        format_with_configs(&src, &filename, &resolve(config.as_ref())?)
+    }
+
+    fn format_range(
+        src: String,
+        filename: String,
+        range: types::TextRange,
+        config: Option<WebConfig>,
+    ) -> Result<types::PrintedRange, String> {
+        let range = TextRange::from(range);
+        range.check(&src)?;
+
+        let configs = resolve(config.as_ref())?;
+        let printed = format_range_with_configs(&src, &filename, range, &configs)?;
+
+        Ok(printed.into())
+    }
+
+    fn check(
+        src: String,
+        filename: String,
+        config: Option<WebConfig>,
+    ) -> Result<types::CheckResult, String> {
+        let code = format_with_configs(&src, &filename, &resolve(config.as_ref())?)?;
+
+        Ok(CheckResult::new(&src, &code).into())
+    }
+}
+
+export!(Component);
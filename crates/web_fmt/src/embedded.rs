@@ -0,0 +1,215 @@
+//! Code blocks embedded in markup, e.g. `<script>` and `<style>` blocks and `style` attributes.
+
+use common::SourceError;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[cfg(any(feature = "script", feature = "oxc"))]
+use crate::format_script::{self, ScriptConfig};
+use crate::language::Language;
+use crate::verify::{self, Checks, Verify};
+use crate::{JSConfig, LanguageConfigs};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "EmbeddedHints")]
+    pub type JsEmbeddedHints;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const EMBEDDED: &'static str = r#"
+/**
+ * What the markup formatter tells about a block embedded in markup.
+ */
+export interface EmbeddedHints {
+	/** The extension of the block language, e.g. `ts` or `css`. */
+	ext: string;
+	/** The print width left for the block. */
+	printWidth: number;
+	/** Whether the block is an attribute value, e.g. of `style`. */
+	attr: boolean;
+	/** Whether the markup quotes attributes with double quotes. */
+	doubleQuotes: boolean;
+}
+"#;
+
+/// What the markup formatter tells about an embedded block.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EmbeddedHints {
+    pub(crate) ext: String,
+    pub(crate) print_width: usize,
+    pub(crate) attr: bool,
+    pub(crate) double_quotes: bool,
+}
+
+/// Formats a block embedded in markup, with the same configuration as `format`.
+///
+/// The markup module of the lazily loaded build hands it the blocks of languages it lacks.
+#[wasm_bindgen(js_name = formatEmbedded)]
+pub fn format_embedded(
+    #[wasm_bindgen(param_description = "The code of the block")] src: &str,
+    #[wasm_bindgen(param_description = "The filename of the markup embedding the block")]
+    filename: &str,
+    #[wasm_bindgen(param_description = "The language and layout of the block")]
+    hints: JsEmbeddedHints,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+) -> Result<String, String> {
+    let hints: EmbeddedHints =
+        serde_wasm_bindgen::from_value(hints.into()).map_err(|e| e.to_string())?;
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+
+    configs.embedded_blocks(filename).format(src, &hints)
+}
+
+/// Formats the blocks embedded in the markup of `filename`.
+pub(crate) struct EmbeddedBlocks {
+    pub(crate) filename: String,
+    #[cfg(any(feature = "script", feature = "oxc"))]
+    pub(crate) script_config: ScriptConfig,
+    #[cfg(feature = "style")]
+    pub(crate) style_config: malva_fmt::config::MalvaConfig,
+    #[cfg(feature = "json")]
+    pub(crate) json_config: json_fmt::JsonConfig,
+    pub(crate) verify: Option<Checks>,
+}
+
+impl EmbeddedBlocks {
+    /// Whether blocks of `language` are formatted at all; the others are kept as they are.
+    pub(crate) fn formats(language: Language) -> bool {
+        matches!(language, Language::Script | Language::Style | Language::Json)
+    }
+
+    /// Formats a block, then runs the configured checks on it.
+    pub(crate) fn format(&self, src: &str, hints: &EmbeddedHints) -> Result<String, String> {
+        let code = self.format_block(src, hints)?;
+
+        self.verify(src, code, hints, |code| self.format_block(code, hints))
+    }
+
+    /// Runs the configured checks on `code`, the block `src` formatted, which `format_again`
+    /// formats a second time.
+    fn verify(
+        &self,
+        src: &str,
+        code: String,
+        hints: &EmbeddedHints,
+        format_again: impl FnOnce(&str) -> Result<String, String>,
+    ) -> Result<String, String> {
+        let Some(checks) = &self.verify else {
+            return Ok(code);
+        };
+        let ext = &hints.ext;
+        let block = if hints.attr {
+            format!("a `{ext}` attribute")
+        } else {
+            format!("an embedded `{ext}` block")
+        };
+
+        if checks.contains(Verify::Equivalent) {
+            self.verify_block_equivalence(src, &code, ext)
+                .map_err(|e| format!("{e}, in {block} of {}", self.filename))?;
+        }
+        if checks.contains(Verify::Idempotent) {
+            verify::check_idempotent(&code, &self.filename, ext, Some(&block), format_again)?;
+        }
+
+        Ok(code)
+    }
+
+    #[cfg_attr(
+        not(any(feature = "script", feature = "oxc", feature = "style", feature = "json")),
+        allow(unused_variables)
+    )]
+    fn verify_block_equivalence(
+        &self,
+        src: &str,
+        code: &str,
+        ext: &str,
+    ) -> Result<(), SourceError> {
+        match Language::from_extension(ext) {
+            #[cfg(any(feature = "script", feature = "oxc"))]
+            Some(Language::Script) => {
+                format_script::verify_equivalence(src, code, &self.filename, &self.script_config)
+            }
+            #[cfg(feature = "style")]
+            Some(Language::Style) => {
+                malva_fmt::verify_style_equivalence(src, code, &self.style_config)
+            }
+            #[cfg(feature = "json")]
+            Some(Language::Json) => json_fmt::verify_json_equivalence(src, code),
+            _ => Ok(()),
+        }
+    }
+
+    // `hints` goes unused when the languages using it are left out.
+    #[cfg_attr(
+        not(any(feature = "script", feature = "oxc", feature = "style", feature = "json")),
+        allow(unused_variables)
+    )]
+    fn format_block(&self, src: &str, hints: &EmbeddedHints) -> Result<String, String> {
+        match Language::from_extension(&hints.ext) {
+            #[cfg(any(feature = "script", feature = "oxc"))]
+            Some(Language::Script) => format_script::format_embedded(
+                src,
+                &self.filename,
+                &hints.ext,
+                self.script_config.clone().with_line_width(hints.print_width as u16),
+            ),
+            #[cfg(feature = "style")]
+            Some(Language::Style) => {
+                let mut config = self.style_config.clone().with_print_width(hints.print_width);
+
+                if hints.attr {
+                    let quotes = if hints.double_quotes {
+                        malva::config::Quotes::AlwaysSingle
+                    } else {
+                        malva::config::Quotes::AlwaysDouble
+                    };
+
+                    config =
+                        config.with_quotes(quotes).with_single_line_top_level_declarations(true);
+                }
+
+                malva_fmt::format_style_with_config(src, &self.filename, config)
+            }
+            #[cfg(feature = "json")]
+            Some(Language::Json) => json_fmt::format_json_with_config(
+                src,
+                self.json_config.clone().with_line_width(hints.print_width as u16),
+            ),
+            Some(language) if Self::formats(language) => Err(language.not_compiled_in()),
+            _ => Ok(src.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_embedded_blocks_that_are_not_idempotent() {
+        let config = serde_json::json!({ "verify": "idempotent" });
+        let blocks = LanguageConfigs::resolve_json(&config).unwrap().embedded_blocks("App.vue");
+        let hints =
+            EmbeddedHints { ext: "ts".into(), print_width: 80, attr: false, double_quotes: true };
+
+        let code = "let a = 1;\n".to_string();
+        let error = blocks
+            .verify(&code, code.clone(), &hints, |code| Ok(code.replace('1', "2")))
+            .unwrap_err();
+
+        assert!(error.starts_with(
+            "script formatting of an embedded `ts` block of App.vue is not idempotent, the second pass changed:\n"
+        ));
+        assert!(error.contains("-let a = 1;\n+let a = 2;\n"));
+
+        let error = blocks
+            .verify(&code, code.clone(), &hints, |_| Err("unexpected token".into()))
+            .unwrap_err();
+        assert!(error.ends_with("the output fails to format again: unexpected token"));
+
+        assert_eq!(blocks.verify(&code, code.clone(), &hints, |code| Ok(code.into())), Ok(code));
+    }
+}
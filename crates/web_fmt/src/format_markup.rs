@@ -1,15 +1,65 @@
-use biome_fmt::BiomeConfig;
+use std::cell::RefCell;
+
 use common::LayoutConfig;
 use markup_fmt::{EmbeddedFormatter, FormatMarkup, Hints};
 use wasm_bindgen::prelude::*;
 
+use crate::embedded::{EmbeddedBlocks, EmbeddedHints};
+#[cfg(any(feature = "script", feature = "oxc"))]
 use crate::format_script;
+#[cfg(feature = "style")]
 use crate::format_style;
+use crate::language::Language;
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "MarkupConfig")]
     pub type Config;
+
+    #[wasm_bindgen(typescript_type = "EmbeddedFormatter")]
+    pub type JsEmbeddedFormatter;
+
+    #[wasm_bindgen(method, catch, js_name = call)]
+    fn call(
+        this: &JsEmbeddedFormatter,
+        this_arg: &JsValue,
+        src: &str,
+        hints: JsValue,
+    ) -> Result<JsValue, JsValue>;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const EMBEDDED_FORMATTER: &'static str = r#"
+/**
+ * Formats a block embedded in markup, returning its formatted code.
+ */
+export type EmbeddedFormatter = (src: string, hints: EmbeddedHints) => string;
+"#;
+
+// A JS function belongs to the thread that created it and cannot be shared, so the fallback
+// is only seen on the thread it was set on. The `wasm-threads` build, whose batches run on
+// workers, refuses to set one rather than leave the workers without it.
+thread_local! {
+    static EMBEDDED_FALLBACK: RefCell<Option<JsValue>> = const { RefCell::new(None) };
+}
+
+/// Sets the formatter of the blocks embedded in markup whose language is not compiled in,
+/// e.g. to hand them to the module of their language in the lazily loaded build.
+/// Without one, such blocks fail to format.
+///
+/// Fails in the `wasm-threads` build, whose worker threads could not call it.
+#[wasm_bindgen(js_name = setEmbeddedFormatter)]
+pub fn set_embedded_formatter(
+    #[wasm_bindgen(param_description = "The formatter of the blocks, or undefined to unset it")]
+    formatter: Option<JsEmbeddedFormatter>,
+) -> Result<(), String> {
+    if cfg!(feature = "wasm-threads") && formatter.is_some() {
+        return Err("an embedded formatter cannot be set in the wasm-threads build, \
+                    as its worker threads cannot call it"
+            .into());
+    }
+    EMBEDDED_FALLBACK.set(formatter.map(Into::into));
+    Ok(())
 }
 
 /// Formats the given HTML/Vue/Svelte/Astro code with the provided Configuration.
@@ -29,17 +79,17 @@ pub fn format_markup(
         .map_err(|e| e.to_string())?
         .unwrap_or_default();
 
-    let style_config = format_style::produce_style_config(None, &markup_config.layout);
-    let script_config = format_script::produce_script_config(None, &markup_config.layout);
-    let json_config = LayoutConfig::default().fill_empty_with(&markup_config.layout);
-
-    let formatter = EmbeddedCodeFormatter {
+    let blocks = EmbeddedBlocks {
         filename: filename.to_string(),
-        markup_config: markup_config.clone(),
-        script_config,
-        style_config,
-        json_config,
+        #[cfg(any(feature = "script", feature = "oxc"))]
+        script_config: format_script::produce_script_config(None, &markup_config.layout),
+        #[cfg(feature = "style")]
+        style_config: format_style::produce_style_config(None, &markup_config.layout),
+        #[cfg(feature = "json")]
+        json_config: LayoutConfig::default().fill_empty_with(&markup_config.layout).into(),
+        verify: None,
     };
+    let formatter = EmbeddedCodeFormatter::new(blocks, &markup_config);
 
     FormatMarkup::new(src, filename)
         .config(markup_config.into())
@@ -54,48 +104,57 @@ pub(crate) fn produce_markup_config(
     base_config.unwrap_or_default().fill_empty_layout_with(default_layout)
 }
 
+/// Formats the blocks embedded in markup, handing those of languages not compiled in
+/// to the formatter set by [`set_embedded_formatter`].
 pub(crate) struct EmbeddedCodeFormatter {
-    pub(crate) filename: String,
-    pub(crate) markup_config: markup_fmt::config::MarkupConfig,
-    pub(crate) script_config: BiomeConfig,
-    pub(crate) style_config: malva_fmt::config::MalvaConfig,
-    pub(crate) json_config: LayoutConfig,
+    blocks: EmbeddedBlocks,
+    double_quotes: bool,
+}
+
+impl EmbeddedCodeFormatter {
+    pub(crate) fn new(
+        blocks: EmbeddedBlocks,
+        markup_config: &markup_fmt::config::MarkupConfig,
+    ) -> Self {
+        let double_quotes =
+            matches!(markup_config.quotes(), markup_fmt_core::config::Quotes::Double);
+        Self { blocks, double_quotes }
+    }
 }
 
 impl EmbeddedFormatter for EmbeddedCodeFormatter {
     fn format(&self, src: &str, hints: Hints) -> Result<String, String> {
         let Hints { print_width, attr, ext, .. } = hints;
-        match ext.as_bytes() {
-            b"js" | b"ts" | b"mjs" | b"cjs" | b"jsx" | b"tsx" | b"mjsx" | b"cjsx" | b"mtsx" => {
-                biome_fmt::format_script_with_config(
-                    src,
-                    &self.filename,
-                    self.script_config.clone().with_line_width(print_width as u16),
-                )
-            }
-            b"css" | b"scss" | b"sass" | b"less" => {
-                let mut config = self.style_config.clone().with_print_width(print_width);
-
-                if attr {
-                    let quotes = if let markup_fmt_core::config::Quotes::Double =
-                        self.markup_config.quotes()
-                    {
-                        malva::config::Quotes::AlwaysSingle
-                    } else {
-                        malva::config::Quotes::AlwaysDouble
-                    };
-
-                    config =
-                        config.with_quotes(quotes).with_single_line_top_level_declarations(true);
-                }
-
-                malva_fmt::format_style_with_config(src, &self.filename, config)
+        let hints = EmbeddedHints {
+            ext: ext.to_string(),
+            print_width,
+            attr,
+            double_quotes: self.double_quotes,
+        };
+
+        let fallback = match Language::from_extension(ext) {
+            Some(language) if EmbeddedBlocks::formats(language) && !language.is_compiled_in() => {
+                EMBEDDED_FALLBACK.with_borrow(Clone::clone)
             }
-            b"json" | b"jsonc" => json_fmt::format_json_with_config(
-                src,
-                self.json_config.clone().with_line_width(print_width as u16).into(),
-            ),
-            _ => Ok(src.to_string()),
+            _ => None,
+        };
+        match fallback {
+            Some(fallback) => format_with_fallback(&fallback, src, &hints),
+            None => self.blocks.format(src, &hints),
         }
     }
 }
+
+fn format_with_fallback(
+    fallback: &JsValue,
+    src: &str,
+    hints: &EmbeddedHints,
+) -> Result<String, String> {
+    let hints = serde_wasm_bindgen::to_value(hints).map_err(|e| e.to_string())?;
+    let code = fallback
+        .unchecked_ref::<JsEmbeddedFormatter>()
+        .call(&JsValue::UNDEFINED, src, hints)
+        .map_err(|e| e.as_string().unwrap_or_else(|| format!("{e:?}")))?;
+
+    code.as_string().ok_or_else(|| "the embedded formatter did not return a string".into())
+}
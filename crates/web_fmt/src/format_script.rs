@@ -1,6 +1,13 @@
+//! Scripts, formatted by Biome, or by OXC with the `oxc` feature.
+
 use common::LayoutConfig;
 use wasm_bindgen::prelude::*;
 
+#[cfg(not(feature = "oxc"))]
+pub(crate) use self::biome::*;
+#[cfg(feature = "oxc")]
+pub(crate) use self::oxc::*;
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "ScriptConfig")]
@@ -23,12 +30,138 @@ pub fn format_script(
         .map_err(|op| op.to_string())?
         .unwrap_or_default();
 
-    biome_fmt::format_script_with_config(src, filename, config)
+    format(src, filename, config)
 }
 
 pub(crate) fn produce_script_config(
-    config: Option<biome_fmt::BiomeConfig>,
+    config: Option<ScriptConfig>,
     default_layout: &LayoutConfig,
-) -> biome_fmt::BiomeConfig {
+) -> ScriptConfig {
     config.unwrap_or_default().fill_empty_layout_with(default_layout)
 }
+
+#[cfg(not(feature = "oxc"))]
+mod biome {
+    pub(crate) use biome_fmt::BiomeConfig as ScriptConfig;
+    use common::{FormattedRanges, FormattedWithCursor, PrintedRange, SourceError, TextRange};
+
+    pub(crate) fn format(
+        src: &str,
+        filename: &str,
+        config: ScriptConfig,
+    ) -> Result<String, String> {
+        biome_fmt::format_script_with_config(src, filename, config)
+    }
+
+    /// Formats a `<script>` block. Biome takes its language from `filename`, so `ext` is unused.
+    pub(crate) fn format_embedded(
+        src: &str,
+        filename: &str,
+        _ext: &str,
+        config: ScriptConfig,
+    ) -> Result<String, String> {
+        biome_fmt::format_script_with_config(src, filename, config)
+    }
+
+    pub(crate) fn format_with_cursor(
+        src: &str,
+        filename: &str,
+        cursor_offset: u32,
+        config: ScriptConfig,
+    ) -> Result<FormattedWithCursor, String> {
+        biome_fmt::format_script_with_cursor(src, filename, cursor_offset, config)
+    }
+
+    pub(crate) fn format_range(
+        src: &str,
+        range: TextRange,
+        filename: &str,
+        config: ScriptConfig,
+    ) -> Result<PrintedRange, String> {
+        biome_fmt::format_script_range_with_config(src, range, filename, config)
+    }
+
+    pub(crate) fn format_ranges(
+        src: &str,
+        ranges: &[TextRange],
+        filename: &str,
+        config: ScriptConfig,
+    ) -> Result<FormattedRanges, String> {
+        biome_fmt::format_script_ranges_with_config(src, ranges, filename, config)
+    }
+
+    /// Checks that `code`, the formatted version of `src`, has the same syntax tree.
+    pub(crate) fn verify_equivalence(
+        src: &str,
+        code: &str,
+        filename: &str,
+        config: &ScriptConfig,
+    ) -> Result<(), SourceError> {
+        biome_fmt::verify_script_equivalence(src, code, filename, config)
+    }
+}
+
+#[cfg(feature = "oxc")]
+mod oxc {
+    use common::{FormattedRanges, FormattedWithCursor, PrintedRange, SourceError, TextRange};
+    use oxc_fmt::FormatScript;
+    pub(crate) use oxc_fmt::OxFmtOptions as ScriptConfig;
+
+    pub(crate) fn format(
+        src: &str,
+        filename: &str,
+        config: ScriptConfig,
+    ) -> Result<String, String> {
+        FormatScript::new(src, filename).config(config)?.format()
+    }
+
+    /// Formats a `<script>` block, whose language is given by `ext` rather than by `filename`.
+    pub(crate) fn format_embedded(
+        src: &str,
+        filename: &str,
+        ext: &str,
+        config: ScriptConfig,
+    ) -> Result<String, String> {
+        FormatScript::new(src, filename).ext(ext).config(config)?.format()
+    }
+
+    pub(crate) fn format_with_cursor(
+        src: &str,
+        filename: &str,
+        cursor_offset: u32,
+        config: ScriptConfig,
+    ) -> Result<FormattedWithCursor, String> {
+        FormatScript::new(src, filename).config(config)?.format_with_cursor(cursor_offset)
+    }
+
+    pub(crate) fn format_range(
+        src: &str,
+        range: TextRange,
+        filename: &str,
+        config: ScriptConfig,
+    ) -> Result<PrintedRange, String> {
+        FormatScript::new(src, filename).config(config)?.range(range).format_range()
+    }
+
+    /// Formats each range on its own, as OXC has no multi-range formatter.
+    pub(crate) fn format_ranges(
+        src: &str,
+        ranges: &[TextRange],
+        filename: &str,
+        config: ScriptConfig,
+    ) -> Result<FormattedRanges, String> {
+        common::format_ranges(src, ranges, |range| {
+            format_range(src, range, filename, config.clone())
+        })
+    }
+
+    /// Checks that `code`, the formatted version of `src`, has the same syntax tree.
+    pub(crate) fn verify_equivalence(
+        src: &str,
+        code: &str,
+        filename: &str,
+        config: &ScriptConfig,
+    ) -> Result<(), SourceError> {
+        oxc_fmt::verify_script_equivalence(src, code, filename, config)
+    }
+}
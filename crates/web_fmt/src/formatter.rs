@@ -0,0 +1,167 @@
+use common::{CheckResult, Location, PrintedRange, SourceError, TextRange};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    JSConfig, JsTextRange, LanguageConfigs, format_encoded_range_with_configs,
+    format_range_with_configs, format_with_configs,
+};
+
+/// An error of formatting a file, for Rust callers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatError {
+    /// The language of the file, named after its Cargo feature, `None` when its extension is unsupported.
+    pub language: Option<String>,
+    /// The 1-based line and column of the source the error points at, if any.
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl FormatError {
+    fn new(filename: &str, error: SourceError) -> Self {
+        let language = crate::language_of(filename).ok();
+        Self { language, location: error.location, message: error.message }
+    }
+}
+
+/// A formatter holding its configuration, resolved once for every language.
+///
+/// Watch-mode tools and editors formatting many files with the same configuration
+/// skip converting the JavaScript config object on every call.
+#[wasm_bindgen]
+pub struct Formatter {
+    configs: LanguageConfigs,
+}
+
+#[wasm_bindgen]
+impl Formatter {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        #[wasm_bindgen(param_description = "Optional formatter config for different languages")]
+        config: Option<JSConfig>,
+    ) -> Result<Formatter, String> {
+        Ok(Self { configs: LanguageConfigs::resolve(config.as_ref())? })
+    }
+
+    /// Formats the given code based on the file extension.
+    pub fn format(
+        &self,
+        #[wasm_bindgen(param_description = "The code to format")] src: &str,
+        #[wasm_bindgen(
+            param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+        )]
+        filename: &str,
+    ) -> Result<String, String> {
+        self.try_format(src, filename).map_err(|e| e.message)
+    }
+
+    /// Formats a range of the given code based on the file extension.
+    #[wasm_bindgen(js_name = formatRange, unchecked_return_type = "PrintedRange")]
+    pub fn format_range(
+        &self,
+        #[wasm_bindgen(param_description = "The code to format")] src: &str,
+        #[wasm_bindgen(
+            param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+        )]
+        filename: &str,
+        #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
+        #[wasm_bindgen(
+            unchecked_param_type = "OffsetEncoding",
+            param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+        )]
+        encoding: Option<String>,
+    ) -> Result<JsValue, String> {
+        format_encoded_range_with_configs(src, filename, range, encoding, &self.configs)
+    }
+
+    /// Checks whether the given code is already formatted, based on the file extension.
+    #[wasm_bindgen(unchecked_return_type = "CheckResult")]
+    pub fn check(
+        &self,
+        #[wasm_bindgen(param_description = "The code to check")] src: &str,
+        #[wasm_bindgen(
+            param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+        )]
+        filename: &str,
+    ) -> Result<JsValue, String> {
+        let result = self.check_code(src, filename).map_err(|e| e.message)?;
+
+        serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
+    }
+}
+
+impl Formatter {
+    /// Creates a formatter from a JSON config object, with the same shape as the JavaScript config.
+    pub fn from_json(config: &serde_json::Value) -> Result<Self, String> {
+        Ok(Self { configs: LanguageConfigs::resolve_json(config)? })
+    }
+
+    /// Formats a range of the given code, with the range and the result in UTF-8 byte offsets.
+    pub fn format_text_range(
+        &self,
+        src: &str,
+        filename: &str,
+        range: TextRange,
+    ) -> Result<PrintedRange, String> {
+        range.check(src)?;
+
+        format_range_with_configs(src, filename, range, &self.configs)
+    }
+
+    /// Formats the given code based on the file extension, with the language of the file and
+    /// the location of the error on failure.
+    pub fn try_format(&self, src: &str, filename: &str) -> Result<String, FormatError> {
+        format_with_configs(src, filename, &self.configs)
+            .map_err(|error| FormatError::new(filename, error))
+    }
+
+    /// Checks whether the given code is already formatted, reporting where it first differs otherwise.
+    pub fn check_code(&self, src: &str, filename: &str) -> Result<CheckResult, FormatError> {
+        let code = self.try_format(src, filename)?;
+
+        Ok(CheckResult::new(src, &code))
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use crate::language::Language;
+
+    #[test]
+    fn picks_the_json_key_order_per_file_without_keeping_it() {
+        let config = serde_json::json!({ "json": { "sortKeys": "schema" } });
+        let formatter = Formatter::from_json(&config).unwrap();
+
+        let package = formatter.configs.json_config("package.json");
+        assert_eq!(package.filename(), Some("package.json"));
+        assert!(package.sort_keys().is_some());
+        assert_eq!(
+            formatter.configs.json_config("tsconfig.json").filename(),
+            Some("tsconfig.json")
+        );
+        assert_eq!(formatter.configs.json.filename(), None);
+    }
+
+    #[test]
+    fn formats_rc_files_as_json() {
+        let config = serde_json::json!({ "json": { "sortKeys": "schema" } });
+        let formatter = Formatter::from_json(&config).unwrap();
+
+        assert_eq!(Language::of_file("config/.babelrc"), Ok(Language::Json));
+        assert!(formatter.format("{ \"a\": 1 }", ".eslintrc").is_ok());
+        assert!(formatter.configs.json_config(".eslintrc").sort_keys().is_some());
+    }
+
+    #[test]
+    fn reports_the_language_of_failing_files() {
+        let formatter = Formatter::from_json(&serde_json::json!({})).unwrap();
+
+        let error = formatter.try_format("x", "a.xyz").unwrap_err();
+        assert_eq!(error.language, None);
+        assert_eq!(error.location, None);
+        assert_eq!(Some(error.message), formatter.format("x", "a.xyz").err());
+
+        let error = formatter.try_format("{ \"a\": bad }", "a.json").unwrap_err();
+        assert_eq!(error.language.as_deref(), Some("json"));
+    }
+}
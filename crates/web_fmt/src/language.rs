@@ -0,0 +1,99 @@
+//! The languages of web_fmt, each compiled in by its Cargo feature.
+
+use std::path::Path;
+
+/// A language chosen by the file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Language {
+    Script,
+    Style,
+    Markup,
+    Json,
+    Graphql,
+}
+
+impl Language {
+    /// Every language, in the order of [`compiled_languages`].
+    const ALL: [Self; 5] = [Self::Script, Self::Style, Self::Markup, Self::Json, Self::Graphql];
+
+    pub(crate) fn from_extension(ext: &str) -> Option<Self> {
+        match ext.as_bytes() {
+            b"js" | b"ts" | b"mjs" | b"cjs" | b"jsx" | b"tsx" | b"mjsx" | b"cjsx" | b"mtsx"
+            | b"ctsx" => Some(Self::Script),
+            b"css" | b"scss" | b"sass" | b"less" => Some(Self::Style),
+            b"html" | b"vue" | b"svelte" | b"astro" | b"jinja" | b"jinja2" | b"twig" => {
+                Some(Self::Markup)
+            }
+            b"json" | b"jsonc" => Some(Self::Json),
+            b"graphql" | b"gql" => Some(Self::Graphql),
+            _ => None,
+        }
+    }
+
+    /// The language of `filename`, whether or not it is compiled in.
+    ///
+    /// The extensionless `.eslintrc` and `.babelrc` are JSON.
+    pub(crate) fn of_file(filename: &str) -> Result<Self, String> {
+        let path = Path::new(filename);
+        if let Some(".eslintrc" | ".babelrc") = path.file_name().and_then(|name| name.to_str()) {
+            return Ok(Self::Json);
+        }
+        let extension = path.extension().ok_or("expected extension")?;
+
+        Self::from_extension(&extension.to_string_lossy())
+            .ok_or_else(|| format!("unsupported file extension: {filename}"))
+    }
+
+    /// The name of the language in messages.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Script => "script",
+            Self::Style => "style",
+            Self::Markup => "markup",
+            Self::Json => "JSON",
+            Self::Graphql => "GraphQL",
+        }
+    }
+
+    /// The Cargo feature compiling the language in.
+    pub(crate) fn feature(self) -> &'static str {
+        match self {
+            Self::Script => "script",
+            Self::Style => "style",
+            Self::Markup => "markup",
+            Self::Json => "json",
+            Self::Graphql => "graphql",
+        }
+    }
+
+    pub(crate) fn is_compiled_in(self) -> bool {
+        match self {
+            Self::Script => cfg!(any(feature = "script", feature = "oxc")),
+            Self::Style => cfg!(feature = "style"),
+            Self::Markup => cfg!(feature = "markup"),
+            Self::Json => cfg!(feature = "json"),
+            Self::Graphql => cfg!(feature = "graphql"),
+        }
+    }
+
+    /// The error of formatting a language that is not compiled in.
+    pub(crate) fn not_compiled_in(self) -> String {
+        let feature = match self {
+            Self::Script => "`script` or `oxc`".to_string(),
+            _ => format!("`{}`", self.feature()),
+        };
+        format!(
+            "{} formatting is not compiled in: build web_fmt with the {feature} feature",
+            self.name()
+        )
+    }
+}
+
+/// The features of the languages compiled in.
+pub(crate) fn compiled_languages() -> Vec<String> {
+    Language::ALL
+        .into_iter()
+        .filter(|language| language.is_compiled_in())
+        .map(|language| language.feature().to_string())
+        .collect()
+}
@@ -1,35 +1,165 @@
+// The core build, without languages, only dispatches and resolves configs.
+#![cfg_attr(
+    not(any(
+        feature = "script",
+        feature = "oxc",
+        feature = "style",
+        feature = "markup",
+        feature = "json",
+        feature = "graphql"
+    )),
+    allow(unused_variables, dead_code)
+)]
+
+mod batch;
+#[cfg(feature = "component")]
+mod component;
+mod embedded;
+#[cfg(feature = "graphql")]
 mod format_graphql;
+#[cfg(feature = "json")]
 mod format_json;
+#[cfg(feature = "markup")]
 mod format_markup;
+#[cfg(any(feature = "script", feature = "oxc"))]
 mod format_script;
+#[cfg(feature = "style")]
 mod format_style;
+mod formatter;
+mod language;
+mod verify;
 
 use std::path::Path;
 
-use common::LayoutConfig;
+pub use batch::{BatchFile, BatchResult};
+use common::{
+    AnyRange, CheckResult, EncodedRange, FormattedRanges, FormattedWithCursor, LayoutConfig,
+    OffsetConverter, OffsetEncoding, Position, PrintedRange, SourceError, TextEdit, TextRange,
+};
+pub use common::{DiffOptions, unified_diff};
+use embedded::EmbeddedBlocks;
+#[cfg(feature = "markup")]
 use format_markup::EmbeddedCodeFormatter as MarkupEmbeddedCodeFormatter;
+pub use formatter::{FormatError, Formatter};
+use language::Language;
+#[cfg(feature = "markup")]
 use markup_fmt::FormatMarkup;
 use serde::Deserialize;
+use verify::{Checks, Verify};
 use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm-threads")]
+pub use wasm_bindgen_rayon::init_thread_pool;
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(typescript_type = "Config")]
     pub type JSConfig;
+
+    #[wasm_bindgen(typescript_type = "TextRange | LineColumnRange")]
+    pub type JsTextRange;
+
+    #[wasm_bindgen(typescript_type = "Array<TextRange | LineColumnRange | LineRange>")]
+    pub type JsRanges;
+
+    #[wasm_bindgen(typescript_type = "DiffOptions")]
+    pub type JsDiffOptions;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const RANGE: &'static str = r#"
+import type {
+	CheckResult,
+	FormattedWithCursor,
+	LineColumn,
+	LineColumnRange,
+	OffsetEncoding,
+	PrintedRange,
+	TextEdit,
+	TextRange,
+} from "./text.d.ts";
+
+export type * from "./text.d.ts";
+
+/**
+ * A range of whole lines, 1-based and inclusive, e.g. a diff hunk.
+ */
+export interface LineRange {
+	startLine: number;
+	endLine: number;
+}
+
+/**
+ * Result of formatting several ranges of code: the whole new code and the formatted ranges,
+ * against the offsets of the original code.
+ */
+export interface FormattedRanges {
+	code: string;
+	ranges: PrintedRange[];
 }
 
+/**
+ * Options of the unified diff between code and its formatted version.
+ */
+export interface DiffOptions {
+	/**
+	 * Number of unchanged lines shown around changes.
+	 * @default 3
+	 */
+	context?: number;
+	/**
+	 * Whether to color the diff with ANSI escape codes.
+	 * @default false
+	 */
+	color?: boolean;
+}
+"#;
+
+/// The config, where the options of languages that are not compiled in are ignored.
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 struct Config {
+    #[cfg(feature = "markup")]
     markup: Option<markup_fmt::config::MarkupConfig>,
-    script: Option<biome_fmt::BiomeConfig>,
+    #[cfg(any(feature = "script", feature = "oxc"))]
+    script: Option<format_script::ScriptConfig>,
+    #[cfg(feature = "style")]
     style: Option<malva_fmt::config::MalvaConfig>,
-    json: Option<LayoutConfig>,
+    #[cfg(feature = "json")]
+    json: Option<json_fmt::JsonConfig>,
+    #[cfg(feature = "graphql")]
     graphql: Option<graphql_fmt::config::GraphqlConfig>,
+    verify: Option<Checks>,
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const LANGUAGE: &'static str = r#"
+/**
+ * A language web_fmt is built with, named after its Cargo feature.
+ */
+export type Language = "script" | "style" | "markup" | "json" | "graphql";
+"#;
+
+/// Lists the languages compiled in. Formatting another language fails with an error naming
+/// the Cargo feature to enable.
+#[wasm_bindgen(unchecked_return_type = "Language[]")]
+pub fn languages() -> Vec<String> {
+    language::compiled_languages()
+}
+
+/// Returns the language of a file, whether or not it is compiled in.
+#[wasm_bindgen(js_name = languageOf, unchecked_return_type = "Language")]
+pub fn language_of(
+    #[wasm_bindgen(
+        param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+    )]
+    filename: &str,
+) -> Result<String, String> {
+    Ok(Language::of_file(filename)?.feature().to_string())
 }
 
 /// Formats the given code based on the file extension with the provided Configuration.
-/// Supports JavaScript, TypeScript, JSX, TSX, CSS, SCSS, Sass, Less, HTML, Vue, Svelte, Astro, JSON, JSONC, GraphQL.
+/// Supports JavaScript, TypeScript, JSX, TSX, CSS, SCSS, Sass, Less, HTML, Vue, Svelte, Astro, JSON, JSONC, GraphQL,
+/// for the languages compiled in.
 #[wasm_bindgen]
 pub fn format(
     #[wasm_bindgen(param_description = "The code to format")] src: &str,
@@ -39,63 +169,441 @@ pub fn format(
     filename: &str,
     #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
 ) -> Result<String, String> {
-    let default_config: ConfigDefault = config
-        .as_ref()
-        .map(|x| serde_wasm_bindgen::from_value(x.into()))
-        .transpose()
-        .map_err(|e| e.to_string())?
-        .unwrap_or_default();
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+
+    Ok(format_with_configs(src, filename, &configs)?)
+}
+
+/// Formats the given code based on the file extension, returning where the cursor ends up in the output.
+///
+/// Scripts and JSON map the cursor through the source markers of their printer. Other languages,
+/// and all of them when the output is verified, map it by diffing the source and the output,
+/// which also covers `<script>` and `<style>` blocks of components.
+#[wasm_bindgen(js_name = formatWithCursor, unchecked_return_type = "FormattedWithCursor")]
+pub fn format_with_cursor(
+    #[wasm_bindgen(param_description = "The code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(
+        unchecked_param_type = "number | LineColumn",
+        param_description = "The cursor position, counted as given by `encoding`"
+    )]
+    cursor_offset: JsValue,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the cursor positions are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let cursor_offset: Position =
+        serde_wasm_bindgen::from_value(cursor_offset).map_err(|e| e.to_string())?;
+    let cursor_offset = offsets.to_utf8(cursor_offset)?;
+
+    let formatted = format_with_cursor_and_configs(src, filename, cursor_offset, &configs)?;
 
-    let config: Config = config
-        .as_ref()
+    serde_wasm_bindgen::to_value(&offsets.encode_cursor(formatted)?).map_err(|e| e.to_string())
+}
+
+/// Formats the given code based on the file extension, returning the minimal edits that turn it
+/// into the formatted code, so that editors can keep undo history, folds and breakpoints.
+#[wasm_bindgen(js_name = formatEdits, unchecked_return_type = "TextEdit[]")]
+pub fn format_edits(
+    #[wasm_bindgen(param_description = "The code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+) -> Result<JsValue, String> {
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+
+    let code = format_with_configs(src, filename, &configs)?;
+    let edits: Vec<TextEdit> = common::text_edits(src, &code);
+
+    serde_wasm_bindgen::to_value(&edits).map_err(|e| e.to_string())
+}
+
+/// Checks whether the given code is already formatted, based on the file extension,
+/// without returning the formatted code.
+#[wasm_bindgen(unchecked_return_type = "CheckResult")]
+pub fn check(
+    #[wasm_bindgen(param_description = "The code to check")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+) -> Result<JsValue, String> {
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+
+    let code = format_with_configs(src, filename, &configs)?;
+
+    serde_wasm_bindgen::to_value(&CheckResult::new(src, &code)).map_err(|e| e.to_string())
+}
+
+/// Formats the given code based on the file extension, returning a unified diff from the code
+/// to its formatted version, or an empty string when it is already formatted.
+#[wasm_bindgen(js_name = formatDiff)]
+pub fn format_diff(
+    #[wasm_bindgen(param_description = "The code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql), also used in the diff headers"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+    #[wasm_bindgen(param_description = "Optional context lines and coloring of the diff")] options: Option<JsDiffOptions>,
+) -> Result<String, String> {
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+    let options: DiffOptions = options
         .map(|x| serde_wasm_bindgen::from_value(x.into()))
         .transpose()
-        .map_err(|e| e.to_string())?
+        .map_err(|op| op.to_string())?
         .unwrap_or_default();
 
-    let extension = Path::new(&filename).extension().ok_or("expected extension")?;
-
-    let script_config = format_script::produce_script_config(
-        config.script,
-        &default_config.script.fill_empty_with(&default_config.default),
-    );
-    let style_config = format_style::produce_style_config(
-        config.style,
-        &default_config.style.fill_empty_with(&default_config.default),
-    );
-    let markup_config = format_markup::produce_markup_config(
-        config.markup,
-        &default_config.markup.fill_empty_with(&default_config.default),
-    );
-    let json_config = config.json.unwrap_or_default().fill_empty_with(&default_config.default);
-    let graphql_config = format_graphql::produce_graphql_config(
-        config.graphql,
-        &default_config.graphql.fill_empty_with(&default_config.default),
-    );
-
-    match extension.as_encoded_bytes() {
-        b"js" | b"ts" | b"mjs" | b"cjs" | b"jsx" | b"tsx" | b"mjsx" | b"cjsx" | b"mtsx"
-        | b"ctsx" => biome_fmt::format_script_with_config(src, filename, script_config),
-        b"css" | b"scss" | b"sass" | b"less" => {
-            malva_fmt::format_style_with_config(src, filename, style_config)
+    let code = format_with_configs(src, filename, &configs)?;
+
+    Ok(unified_diff(src, &code, filename, &options))
+}
+
+fn format_with_cursor_and_configs(
+    src: &str,
+    filename: &str,
+    cursor_offset: u32,
+    configs: &LanguageConfigs,
+) -> Result<FormattedWithCursor, String> {
+    // The output is verified on the diffed path only.
+    match Language::of_file(filename)? {
+        #[cfg(any(feature = "script", feature = "oxc"))]
+        Language::Script if configs.verify.is_none() => {
+            format_script::format_with_cursor(src, filename, cursor_offset, configs.script.clone())
+        }
+        #[cfg(feature = "json")]
+        Language::Json if configs.verify.is_none() => {
+            json_fmt::format_json_with_cursor(src, cursor_offset, configs.json_config(filename))
+        }
+        _ => {
+            FormattedWithCursor::check_cursor(src, cursor_offset)?;
+            let code = format_with_configs(src, filename, configs)?;
+            Ok(FormattedWithCursor::from_diff(src, code, cursor_offset))
         }
-        b"html" | b"vue" | b"svelte" | b"astro" | b"jinja" | b"jinja2" | b"twig" => {
-            let formatter = MarkupEmbeddedCodeFormatter {
-                filename: filename.to_string(),
-                markup_config: markup_config.clone(),
-                script_config,
-                style_config,
-                json_config,
-            };
+    }
+}
+
+fn format_with_configs(
+    src: &str,
+    filename: &str,
+    configs: &LanguageConfigs,
+) -> Result<String, SourceError> {
+    let Some(checks) = &configs.verify else {
+        return format_once(src, filename, configs);
+    };
+
+    let code = format_once(src, filename, configs)?;
+
+    if checks.contains(Verify::Equivalent) {
+        verify_equivalence(src, &code, filename, configs)?;
+    }
+    if checks.contains(Verify::Idempotent) {
+        let extension = Path::new(&filename).extension().unwrap_or_default();
+        verify::check_idempotent(&code, filename, &extension.to_string_lossy(), None, |code| {
+            format_once(code, filename, configs).map_err(String::from)
+        })?;
+    }
+
+    Ok(code)
+}
+
+/// Checks that `code`, the formatted version of `src`, has the same syntax tree.
+///
+/// Markup itself is not compared, but its embedded blocks are when they are formatted.
+#[cfg_attr(not(feature = "style"), allow(unused_variables))]
+fn verify_equivalence(
+    src: &str,
+    code: &str,
+    filename: &str,
+    configs: &LanguageConfigs,
+) -> Result<(), SourceError> {
+    match Language::of_file(filename)? {
+        #[cfg(any(feature = "script", feature = "oxc"))]
+        Language::Script => format_script::verify_equivalence(src, code, filename, &configs.script),
+        #[cfg(feature = "style")]
+        Language::Style => malva_fmt::verify_style_equivalence(src, code, &configs.style),
+        #[cfg(feature = "json")]
+        Language::Json => json_fmt::verify_json_equivalence(src, code),
+        #[cfg(feature = "graphql")]
+        Language::Graphql => graphql_fmt::verify_graphql_equivalence(src, code, &configs.graphql),
+        // The embedded blocks were compared while formatting them.
+        Language::Markup => Ok(()),
+        #[allow(unreachable_patterns)]
+        language => Err(language.not_compiled_in().into()),
+    }
+}
+
+fn format_once(
+    src: &str,
+    filename: &str,
+    configs: &LanguageConfigs,
+) -> Result<String, SourceError> {
+    match Language::of_file(filename)? {
+        #[cfg(any(feature = "script", feature = "oxc"))]
+        Language::Script => Ok(format_script::format(src, filename, configs.script.clone())?),
+        #[cfg(feature = "style")]
+        Language::Style => {
+            Ok(malva_fmt::format_style_with_config(src, filename, configs.style.clone())?)
+        }
+        #[cfg(feature = "markup")]
+        Language::Markup => {
+            let markup_config = configs.markup.clone();
+
+            FormatMarkup::new(src, filename)
+                .config(markup_config.into())
+                .embed_formatter(configs.markup_formatter(filename))
+                .try_format()
+        }
+        #[cfg(feature = "json")]
+        Language::Json => {
+            Ok(json_fmt::format_json_with_config(src, configs.json_config(filename))?)
+        }
+        #[cfg(feature = "graphql")]
+        Language::Graphql => {
+            Ok(graphql_fmt::format_graphql_with_config(src, configs.graphql.clone())?)
+        }
+        #[allow(unreachable_patterns)]
+        language => Err(language.not_compiled_in().into()),
+    }
+}
+
+/// Formats a range of the given code based on the file extension with the provided Configuration.
+///
+/// Scripts and JSON use Biome's range formatter. Stylesheets, GraphQL and markup are formatted
+/// from the smallest enclosing rule, definition or element; a range within a `<script>` or
+/// `<style>` block of a component formats just that block.
+#[wasm_bindgen(js_name = formatRange, unchecked_return_type = "PrintedRange")]
+pub fn format_range(
+    #[wasm_bindgen(param_description = "The code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "The text range to format")] range: JsTextRange,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the range and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+
+    format_encoded_range_with_configs(src, filename, range, encoding, &configs)
+}
+
+/// Formats a range given in the caller's offset encoding, returning the result in that encoding.
+fn format_encoded_range_with_configs(
+    src: &str,
+    filename: &str,
+    range: JsTextRange,
+    encoding: Option<String>,
+    configs: &LanguageConfigs,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let range: EncodedRange =
+        serde_wasm_bindgen::from_value(range.into()).map_err(|e| e.to_string())?;
+    let range = offsets.range_to_utf8(range)?;
+
+    let printed = format_range_with_configs(src, filename, range, configs)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_printed(printed)?).map_err(|e| e.to_string())
+}
+
+fn format_range_with_configs(
+    src: &str,
+    filename: &str,
+    range: TextRange,
+    configs: &LanguageConfigs,
+) -> Result<PrintedRange, String> {
+    match Language::of_file(filename)? {
+        #[cfg(any(feature = "script", feature = "oxc"))]
+        Language::Script => {
+            format_script::format_range(src, range, filename, configs.script.clone())
+        }
+        #[cfg(feature = "style")]
+        Language::Style => {
+            malva_fmt::format_style_range_with_config(src, range, filename, configs.style.clone())
+        }
+        #[cfg(feature = "markup")]
+        Language::Markup => {
+            let markup_config = configs.markup.clone();
 
             FormatMarkup::new(src, filename)
                 .config(markup_config.into())
-                .embed_formatter(formatter)
-                .format()
+                .embed_formatter(configs.markup_formatter(filename))
+                .range(range)
+                .format_range()
+        }
+        #[cfg(feature = "json")]
+        Language::Json => {
+            json_fmt::format_json_range_with_config(src, range, configs.json_config(filename))
+        }
+        #[cfg(feature = "graphql")]
+        Language::Graphql => {
+            graphql_fmt::format_graphql_range_with_config(src, range, configs.graphql.clone())
+        }
+        #[allow(unreachable_patterns)]
+        language => Err(language.not_compiled_in()),
+    }
+}
+
+/// Formats several ranges of the given code in one pass, e.g. the hunks of a diff.
+///
+/// Overlapping ranges are merged, and so are ranges expanded to overlapping nodes.
+/// The formatted ranges and the ranges they replace all refer to the original code.
+#[wasm_bindgen(js_name = formatRanges, unchecked_return_type = "FormattedRanges")]
+pub fn format_ranges(
+    #[wasm_bindgen(param_description = "The code to format")] src: &str,
+    #[wasm_bindgen(
+        param_description = "The filename to determine the language (e.g., .js, .ts, .css, .html, .json, .graphql)"
+    )]
+    filename: &str,
+    #[wasm_bindgen(param_description = "The text or line ranges to format")] ranges: JsRanges,
+    #[wasm_bindgen(param_description = "Optional formatter config for different languages")] config: Option<JSConfig>,
+    #[wasm_bindgen(
+        unchecked_param_type = "OffsetEncoding",
+        param_description = "How the offsets of the ranges and of the result are counted (default: utf8)"
+    )]
+    encoding: Option<String>,
+) -> Result<JsValue, String> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose()?;
+    let offsets = OffsetConverter::new(src, encoding.unwrap_or_default());
+    let ranges: Vec<AnyRange> =
+        serde_wasm_bindgen::from_value(ranges.into()).map_err(|e| e.to_string())?;
+    let ranges =
+        ranges.iter().map(|range| range.to_text_range(&offsets)).collect::<Result<Vec<_>, _>>()?;
+    let configs = LanguageConfigs::resolve(config.as_ref())?;
+
+    let formatted = format_ranges_with_configs(src, filename, &ranges, &configs)?;
+
+    serde_wasm_bindgen::to_value(&offsets.encode_formatted(formatted)?).map_err(|e| e.to_string())
+}
+
+fn format_ranges_with_configs(
+    src: &str,
+    filename: &str,
+    ranges: &[TextRange],
+    configs: &LanguageConfigs,
+) -> Result<FormattedRanges, String> {
+    match Language::of_file(filename)? {
+        #[cfg(any(feature = "script", feature = "oxc"))]
+        Language::Script => {
+            format_script::format_ranges(src, ranges, filename, configs.script.clone())
+        }
+        _ => common::format_ranges(src, ranges, |range| {
+            format_range_with_configs(src, filename, range, configs)
+        }),
+    }
+}
+
+/// Per-language configurations, with the shared layout filled in.
+#[derive(Clone)]
+struct LanguageConfigs {
+    #[cfg(feature = "markup")]
+    markup: markup_fmt::config::MarkupConfig,
+    #[cfg(any(feature = "script", feature = "oxc"))]
+    script: format_script::ScriptConfig,
+    #[cfg(feature = "style")]
+    style: malva_fmt::config::MalvaConfig,
+    #[cfg(feature = "json")]
+    json: json_fmt::JsonConfig,
+    #[cfg(feature = "graphql")]
+    graphql: graphql_fmt::config::GraphqlConfig,
+    verify: Option<Checks>,
+}
+
+impl LanguageConfigs {
+    /// Resolves the configuration of every language, independently of the file.
+    fn resolve(config: Option<&JSConfig>) -> Result<Self, String> {
+        let default_config: ConfigDefault = config
+            .map(|x| serde_wasm_bindgen::from_value(x.into()))
+            .transpose()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default();
+
+        let config: Config = config
+            .map(|x| serde_wasm_bindgen::from_value(x.into()))
+            .transpose()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default();
+
+        Ok(Self::from_parsed(default_config, config))
+    }
+
+    /// Resolves the configuration of every language from a JSON config, for Rust callers.
+    fn resolve_json(config: &serde_json::Value) -> Result<Self, String> {
+        let default_config: ConfigDefault =
+            serde_json::from_value(config.clone()).map_err(|e| e.to_string())?;
+        let config: Config = serde_json::from_value(config.clone()).map_err(|e| e.to_string())?;
+
+        Ok(Self::from_parsed(default_config, config))
+    }
+
+    fn from_parsed(default_config: ConfigDefault, config: Config) -> Self {
+        Self {
+            #[cfg(feature = "markup")]
+            markup: format_markup::produce_markup_config(
+                config.markup,
+                &default_config.markup.fill_empty_with(&default_config.default),
+            ),
+            #[cfg(any(feature = "script", feature = "oxc"))]
+            script: format_script::produce_script_config(
+                config.script,
+                &default_config.script.fill_empty_with(&default_config.default),
+            ),
+            #[cfg(feature = "style")]
+            style: format_style::produce_style_config(
+                config.style,
+                &default_config.style.fill_empty_with(&default_config.default),
+            ),
+            #[cfg(feature = "json")]
+            json: config.json.unwrap_or_default().fill_empty_layout_with(&default_config.default),
+            #[cfg(feature = "graphql")]
+            graphql: format_graphql::produce_graphql_config(
+                config.graphql,
+                &default_config.graphql.fill_empty_with(&default_config.default),
+            ),
+            verify: config.verify,
+        }
+    }
+
+    /// The JSON config for `filename`, which chooses the key order of well-known files.
+    #[cfg(feature = "json")]
+    fn json_config(&self, filename: &str) -> json_fmt::JsonConfig {
+        self.json.clone().with_filename(filename)
+    }
+
+    #[cfg(feature = "markup")]
+    fn markup_formatter(&self, filename: &str) -> MarkupEmbeddedCodeFormatter {
+        MarkupEmbeddedCodeFormatter::new(self.embedded_blocks(filename), &self.markup)
+    }
+
+    fn embedded_blocks(&self, filename: &str) -> EmbeddedBlocks {
+        EmbeddedBlocks {
+            filename: filename.to_string(),
+            #[cfg(any(feature = "script", feature = "oxc"))]
+            script_config: self.script.clone(),
+            #[cfg(feature = "style")]
+            style_config: self.style.clone(),
+            #[cfg(feature = "json")]
+            json_config: self.json_config(filename),
+            verify: self.verify.clone(),
         }
-        b"json" | b"jsonc" => json_fmt::format_json_with_config(src, json_config.into()),
-        b"graphql" | b"gql" => graphql_fmt::format_graphql_with_config(src, graphql_config),
-        _ => Err(format!("unsupported file extension: {filename}")),
     }
 }
 
@@ -104,12 +612,16 @@ pub fn format(
 struct ConfigDefault {
     #[serde(flatten, default)]
     default: LayoutConfig,
+    #[cfg(feature = "markup")]
     #[serde(default)]
     markup: LayoutConfig,
+    #[cfg(any(feature = "script", feature = "oxc"))]
     #[serde(default)]
     script: LayoutConfig,
+    #[cfg(feature = "style")]
     #[serde(default)]
     style: LayoutConfig,
+    #[cfg(feature = "graphql")]
     #[serde(default)]
     graphql: LayoutConfig,
 }
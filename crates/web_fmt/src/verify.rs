@@ -0,0 +1,82 @@
+//! Self-checks run on the formatter output.
+
+use common::{DiffOptions, unified_diff};
+use serde::Deserialize;
+
+use crate::language::Language;
+
+/// Check run on the output before it is returned.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Verify {
+    /// Format the output a second time and fail when it changes again.
+    Idempotent,
+    /// Parse the output and fail when its syntax tree differs from the one of the source.
+    Equivalent,
+}
+
+/// The `verify` option: one check or a list of checks.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub(crate) enum Checks {
+    One(Verify),
+    Many(Vec<Verify>),
+}
+
+impl Checks {
+    pub(crate) fn contains(&self, check: Verify) -> bool {
+        match self {
+            Self::One(one) => *one == check,
+            Self::Many(many) => many.contains(&check),
+        }
+    }
+}
+
+/// Formats `code`, the output of a first pass, again with `format` and fails with a diff of
+/// the two passes when they differ. `block` names the embedded block being formatted, if any.
+pub(crate) fn check_idempotent(
+    code: &str,
+    filename: &str,
+    ext: &str,
+    block: Option<&str>,
+    format: impl FnOnce(&str) -> Result<String, String>,
+) -> Result<(), String> {
+    let language = Language::from_extension(ext).map_or("unknown", Language::name);
+    let location = match block {
+        Some(block) => format!("{block} of {filename}"),
+        None => filename.to_string(),
+    };
+
+    let second = format(code).map_err(|e| {
+        format!("{language} formatting of {location} is not idempotent: the output fails to format again: {e}")
+    })?;
+    if second == code {
+        return Ok(());
+    }
+
+    let diff = unified_diff(code, &second, filename, &DiffOptions::default());
+    Err(format!(
+        "{language} formatting of {location} is not idempotent, the second pass changed:\n{diff}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_files_that_are_not_idempotent() {
+        let code = "a {\n  color: red;\n}\n";
+
+        let error =
+            check_idempotent(code, "app.css", "css", None, |code| Ok(code.replace("red", "blue")))
+                .unwrap_err();
+        assert_eq!(
+            error,
+            "style formatting of app.css is not idempotent, the second pass changed:\n\
+             --- a/app.css\n+++ b/app.css\n@@ -1,3 +1,3 @@\n a {\n-  color: red;\n+  color: blue;\n }\n"
+        );
+
+        assert!(check_idempotent(code, "app.css", "css", None, |code| Ok(code.into())).is_ok());
+    }
+}
@@ -0,0 +1,32 @@
+#!/usr/bin/env deno test --allow-read --parallel
+import { assertEquals } from "jsr:@std/assert";
+import { expandGlob } from "jsr:@std/fs";
+import { fromFileUrl, relative } from "jsr:@std/path";
+
+import { format } from "../pkg/web_fmt_lazy.js";
+
+const test_root = fromFileUrl(import.meta.resolve("../test_data"));
+
+Deno.test("lazy is the ./lazy export", async () => {
+	const pkg = JSON.parse(await Deno.readTextFile(fromFileUrl(import.meta.resolve("../pkg/package.json"))));
+	assertEquals(pkg.exports["./lazy"].default, "./web_fmt_lazy.js");
+});
+
+// The lazily loaded modules format like the full build, embedded blocks included.
+for await (const { path: input_path, name: file_name } of expandGlob("**/*.{json,tsx,vue,html}", {
+	root: test_root,
+})) {
+	if (file_name.startsWith(".")) {
+		Deno.test.ignore(input_path, () => {});
+		continue;
+	}
+
+	const case_name = relative(test_root, input_path);
+	const snap_path = input_path + ".snap";
+	const [input, expected] = await Promise.all([Deno.readTextFile(input_path), Deno.readTextFile(snap_path)]);
+
+	Deno.test(`lazy: ${case_name}`, async () => {
+		const actual = await format(input, input_path);
+		assertEquals(actual, expected);
+	});
+}
@@ -0,0 +1 @@
+../../web_fmt/extra/graphql_options.d.ts
\ No newline at end of file
@@ -0,0 +1 @@
+../../web_fmt/extra/layout_config.d.ts
\ No newline at end of file
@@ -0,0 +1 @@
+../../web_fmt/extra/options.d.ts
\ No newline at end of file
@@ -0,0 +1 @@
+../../web_fmt/extra/sort_imports.d.ts
\ No newline at end of file
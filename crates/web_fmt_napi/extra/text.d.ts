@@ -0,0 +1 @@
+../../web_fmt/extra/text.d.ts
\ No newline at end of file
@@ -0,0 +1,63 @@
+/**
+ * Native formatter for web development (HTML/CSS/JS/JSON/GraphQL), as a Node-API addon.
+ *
+ * It has the functions and the config of `@wasm-fmt/web_fmt`, so switching is a change of import.
+ *
+ * @example
+ * ```ts
+ * import { format } from "@wasm-fmt/web_fmt_napi";
+ *
+ * const input = "<div>  hello  </div>";
+ * const output = format(input, "index.html");
+ * ```
+ *
+ * @module
+ */
+import type { Config } from "./options.d.ts";
+import type { CheckResult, LineColumnRange, OffsetEncoding, PrintedRange, TextRange } from "./text.d.ts";
+
+export type * from "./options.d.ts";
+export type * from "./text.d.ts";
+
+/**
+ * A file to format in a batch.
+ */
+export interface BatchFile {
+	filename: string;
+	source: string;
+}
+
+/**
+ * Result of formatting one file of a batch: the formatted code, or the error of this file alone.
+ */
+export type BatchResult =
+	| { filename: string; code: string; error?: undefined }
+	| { filename: string; code?: undefined; error: string };
+
+/**
+ * Formats the given code based on the file extension with the provided Configuration.
+ */
+export declare function format(src: string, filename: string, config?: Config | null): string;
+
+/**
+ * Formats a range of the given code based on the file extension with the provided Configuration.
+ */
+export declare function formatRange(
+	src: string,
+	filename: string,
+	range: TextRange | LineColumnRange,
+	config?: Config | null,
+	encoding?: OffsetEncoding | null,
+): PrintedRange;
+
+/**
+ * Checks whether the given code is already formatted, based on the file extension,
+ * without returning the formatted code.
+ */
+export declare function check(src: string, filename: string, config?: Config | null): CheckResult;
+
+/**
+ * Formats many files in one call, in parallel on a thread pool, sharing the resolved configuration across files.
+ * Errors are reported per file.
+ */
+export declare function formatBatch(files: BatchFile[], config?: Config | null): BatchResult[];
@@ -0,0 +1,7 @@
+/* @ts-self-types="./web_fmt_napi.d.ts" */
+import { createRequire } from "node:module";
+
+const require = createRequire(import.meta.url);
+const { format, formatRange, check, formatBatch } = require("./web_fmt_napi.node");
+
+export { format, formatRange, check, formatBatch };
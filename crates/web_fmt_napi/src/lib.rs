@@ -0,0 +1,72 @@
+//! web_fmt as a native Node-API addon, with the functions of its wasm package.
+//!
+//! Batches are formatted in parallel, on the rayon thread pool.
+
+use common::{EncodedRange, OffsetConverter, OffsetEncoding};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::Value;
+use web_fmt::{BatchFile, Formatter};
+
+/// Resolves the config as the wasm package does, from the same JavaScript object.
+fn formatter(config: Option<Value>) -> Result<Formatter> {
+    let config = config.unwrap_or_else(|| Value::Object(Default::default()));
+
+    Formatter::from_json(&config).map_err(Error::from_reason)
+}
+
+fn to_js<T: serde::Serialize>(value: &T) -> Result<Value> {
+    serde_json::to_value(value).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+fn from_js<T: serde::de::DeserializeOwned>(value: Value) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Formats the given code based on the file extension with the provided Configuration.
+#[napi]
+pub fn format(src: String, filename: String, config: Option<Value>) -> Result<String> {
+    formatter(config)?.format(&src, &filename).map_err(Error::from_reason)
+}
+
+/// Formats a range of the given code based on the file extension with the provided Configuration.
+#[napi(js_name = "formatRange")]
+pub fn format_range(
+    src: String,
+    filename: String,
+    range: Value,
+    config: Option<Value>,
+    encoding: Option<String>,
+) -> Result<Value> {
+    let encoding = encoding.as_deref().map(str::parse::<OffsetEncoding>).transpose();
+    let encoding = encoding.map_err(Error::from_reason)?;
+    let offsets = OffsetConverter::new(&src, encoding.unwrap_or_default());
+    let range: EncodedRange = from_js(range)?;
+    let range = offsets.range_to_utf8(range).map_err(Error::from_reason)?;
+
+    let printed =
+        formatter(config)?.format_text_range(&src, &filename, range).map_err(Error::from_reason)?;
+
+    to_js(&offsets.encode_printed(printed).map_err(Error::from_reason)?)
+}
+
+/// Checks whether the given code is already formatted, based on the file extension,
+/// without returning the formatted code.
+#[napi]
+pub fn check(src: String, filename: String, config: Option<Value>) -> Result<Value> {
+    let result = formatter(config)?
+        .check_code(&src, &filename)
+        .map_err(|e| Error::from_reason(e.message))?;
+
+    to_js(&result)
+}
+
+/// Formats many files in one call, in parallel, sharing the resolved configuration across files.
+///
+/// Errors are reported per file, so that one broken file does not abort the batch.
+#[napi(js_name = "formatBatch")]
+pub fn format_batch(files: Value, config: Option<Value>) -> Result<Value> {
+    let files: Vec<BatchFile> = from_js(files)?;
+
+    to_js(&formatter(config)?.format_batch(&files))
+}
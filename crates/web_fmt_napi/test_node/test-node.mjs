@@ -0,0 +1,41 @@
+#!/usr/bin/env node --test
+import assert from "node:assert/strict";
+import { glob, readFile } from "node:fs/promises";
+import path from "node:path";
+import { test } from "node:test";
+import { fileURLToPath } from "node:url";
+
+import { format, formatBatch } from "../pkg/web_fmt_napi.js";
+
+// The addon formats like the wasm package, so it shares its snapshots.
+const test_root = fileURLToPath(import.meta.resolve("../../web_fmt/test_data"));
+
+const files = [];
+
+for await (const case_name of glob("**/*.{json,tsx,vue,html}", { cwd: test_root })) {
+	const file_name = path.basename(case_name);
+	if (file_name.startsWith(".")) {
+		test.skip(case_name, () => {});
+		continue;
+	}
+
+	const full_path = path.join(test_root, case_name);
+	const snap_path = full_path + ".snap";
+
+	const [input, expected] = await Promise.all([readFile(full_path, "utf-8"), readFile(snap_path, "utf-8")]);
+	files.push({ filename: case_name, source: input, expected });
+
+	test(case_name, () => {
+		const actual = format(input, case_name);
+		assert.equal(actual, expected);
+	});
+}
+
+test("formatBatch", () => {
+	const results = formatBatch(files.map(({ filename, source }) => ({ filename, source })));
+
+	assert.deepEqual(
+		results,
+		files.map(({ filename, expected }) => ({ filename, code: expected })),
+	);
+});
@@ -0,0 +1,253 @@
+//! web_fmt as a Python module, with the config of its wasm package as a `dict`.
+//!
+//! Formatting runs with the GIL released, and batches are formatted in parallel on the rayon thread pool.
+
+use common::Location;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use serde_json::{Map, Value};
+use web_fmt::BatchFile;
+
+create_exception!(
+    web_fmt,
+    FormatError,
+    PyValueError,
+    "Raised when code fails to format.\n\n\
+     `language` is the language of the file, or `None` when its extension is unsupported. \
+     `span` is the `(start, end)` character offsets of the source the error points at, if any, \
+     and `message` is the error of the formatter."
+);
+
+/// A formatter holding its configuration, resolved once for every language.
+#[pyclass(frozen, module = "web_fmt")]
+struct Formatter {
+    inner: web_fmt::Formatter,
+}
+
+#[pymethods]
+impl Formatter {
+    #[new]
+    #[pyo3(signature = (config = None))]
+    fn new(config: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = match config {
+            Some(config) => to_json(config.as_any())?,
+            None => Value::Object(Map::new()),
+        };
+        let inner = web_fmt::Formatter::from_json(&config).map_err(PyValueError::new_err)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Formats the given code based on the file extension.
+    fn format(&self, py: Python<'_>, src: &str, filename: &str) -> PyResult<String> {
+        py.detach(|| self.inner.try_format(src, filename)).map_err(|e| format_error(py, src, e))
+    }
+
+    /// Checks whether the given code is already formatted, based on the file extension.
+    fn check(&self, py: Python<'_>, src: &str, filename: &str) -> PyResult<CheckResult> {
+        let result = py
+            .detach(|| self.inner.check_code(src, filename))
+            .map_err(|e| format_error(py, src, e))?;
+
+        Ok(CheckResult {
+            formatted: result.formatted,
+            first_difference: result.first_difference.map(|x| (x.line, x.column)),
+        })
+    }
+
+    /// Formats many `(filename, source)` files in parallel.
+    ///
+    /// Returns the formatted code of each file, in order, or the `FormatError` of the files
+    /// that failed to format, so that one broken file does not abort the batch.
+    fn format_batch(&self, py: Python<'_>, files: Vec<(String, String)>) -> Vec<Py<PyAny>> {
+        let files: Vec<_> =
+            files.into_iter().map(|(filename, source)| BatchFile { filename, source }).collect();
+        let results = py.detach(|| self.inner.try_format_batch(&files));
+
+        files
+            .iter()
+            .zip(results)
+            .map(|(file, result)| match result {
+                Ok(code) => PyString::new(py, &code).into_any().unbind(),
+                Err(error) => format_error(py, &file.source, error).into_value(py).into_any(),
+            })
+            .collect()
+    }
+}
+
+/// Result of checking whether code is already formatted. It is truthy when the code is.
+#[pyclass(frozen, get_all, module = "web_fmt")]
+struct CheckResult {
+    formatted: bool,
+    /// The 1-based line and column where the source first differs from the formatted code.
+    first_difference: Option<(u32, u32)>,
+}
+
+#[pymethods]
+impl CheckResult {
+    fn __bool__(&self) -> bool {
+        self.formatted
+    }
+
+    fn __repr__(&self) -> String {
+        let first_difference = match self.first_difference {
+            Some((line, column)) => format!("({line}, {column})"),
+            None => "None".into(),
+        };
+        let formatted = if self.formatted { "True" } else { "False" };
+        format!("CheckResult(formatted={formatted}, first_difference={first_difference})")
+    }
+}
+
+/// Formats the given code based on the file extension with the provided configuration.
+#[pyfunction]
+#[pyo3(signature = (src, filename, config = None))]
+fn format(
+    py: Python<'_>,
+    src: &str,
+    filename: &str,
+    config: Option<&Bound<'_, PyDict>>,
+) -> PyResult<String> {
+    Formatter::new(config)?.format(py, src, filename)
+}
+
+/// Checks whether the given code is already formatted, based on the file extension.
+#[pyfunction]
+#[pyo3(signature = (src, filename, config = None))]
+fn check(
+    py: Python<'_>,
+    src: &str,
+    filename: &str,
+    config: Option<&Bound<'_, PyDict>>,
+) -> PyResult<CheckResult> {
+    Formatter::new(config)?.check(py, src, filename)
+}
+
+/// Formats many `(filename, source)` files in parallel, sharing the configuration across files.
+#[pyfunction]
+#[pyo3(signature = (files, config = None))]
+fn format_batch(
+    py: Python<'_>,
+    files: Vec<(String, String)>,
+    config: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<Py<PyAny>>> {
+    Ok(Formatter::new(config)?.format_batch(py, files))
+}
+
+/// Lists the languages compiled in.
+#[pyfunction]
+fn languages() -> Vec<String> {
+    web_fmt::languages()
+}
+
+/// Returns the language of a file, whether or not it is compiled in.
+#[pyfunction]
+fn language_of(filename: &str) -> PyResult<String> {
+    web_fmt::language_of(filename).map_err(PyValueError::new_err)
+}
+
+#[pymodule]
+#[pyo3(name = "web_fmt")]
+fn web_fmt_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("FormatError", m.py().get_type::<FormatError>())?;
+    m.add_class::<Formatter>()?;
+    m.add_class::<CheckResult>()?;
+    m.add_function(wrap_pyfunction!(format, m)?)?;
+    m.add_function(wrap_pyfunction!(check, m)?)?;
+    m.add_function(wrap_pyfunction!(format_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(languages, m)?)?;
+    m.add_function(wrap_pyfunction!(language_of, m)?)?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+
+    Ok(())
+}
+
+/// Builds the `FormatError` of formatting `src`.
+fn format_error(py: Python<'_>, src: &str, error: web_fmt::FormatError) -> PyErr {
+    let web_fmt::FormatError { language, location, message } = error;
+    let span = location.and_then(|location| char_span(src, location));
+
+    let err = FormatError::new_err(message.clone());
+    let value = err.value(py);
+    let attrs = value
+        .setattr("language", language)
+        .and_then(|()| value.setattr("span", span))
+        .and_then(|()| value.setattr("message", message));
+
+    match attrs {
+        Ok(()) => err,
+        Err(e) => e,
+    }
+}
+
+/// The `(start, end)` character offsets of the character at `location`, or of the empty span
+/// at the end of its line.
+fn char_span(src: &str, location: Location) -> Option<(usize, usize)> {
+    let line_index = (location.line as usize).checked_sub(1)?;
+    let column = (location.column as usize).checked_sub(1)?;
+
+    let mut lines = src.split('\n');
+    let line_start: usize =
+        lines.by_ref().take(line_index).map(|line| line.chars().count() + 1).sum();
+    let line_len = lines.next()?.trim_end_matches('\r').chars().count();
+
+    if column > line_len {
+        return None;
+    }
+    let start = line_start + column;
+
+    Some((start, start + usize::from(column < line_len)))
+}
+
+/// Converts a Python config to the JSON config of web_fmt.
+fn to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(value) = value.cast::<PyBool>() {
+        Ok(Value::Bool(value.is_true()))
+    } else if value.is_instance_of::<PyInt>() {
+        if let Ok(value) = value.extract::<i64>() {
+            Ok(value.into())
+        } else {
+            Ok(value.extract::<u64>()?.into())
+        }
+    } else if value.is_instance_of::<PyFloat>() {
+        Ok(value.extract::<f64>()?.into())
+    } else if let Ok(value) = value.cast::<PyString>() {
+        Ok(Value::String(value.to_str()?.to_owned()))
+    } else if let Ok(value) = value.cast::<PyDict>() {
+        let mut map = Map::new();
+        for (key, value) in value.iter() {
+            let key = key
+                .cast_into::<PyString>()
+                .map_err(|_| PyTypeError::new_err("config keys must be strings"))?;
+            map.insert(key.to_str()?.to_owned(), to_json(&value)?);
+        }
+        Ok(Value::Object(map))
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        value.try_iter()?.map(|item| to_json(&item?)).collect()
+    } else {
+        let type_name = value.get_type().name()?;
+        Err(PyTypeError::new_err(format!("unsupported config value of type `{type_name}`")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_locations_to_char_spans() {
+        let src = "a\r\nbé c\nd";
+
+        assert_eq!(char_span(src, Location { line: 1, column: 1 }), Some((0, 1)));
+        assert_eq!(char_span(src, Location { line: 2, column: 3 }), Some((5, 6)));
+        assert_eq!(char_span(src, Location { line: 2, column: 5 }), Some((7, 7)));
+        assert_eq!(char_span(src, Location { line: 3, column: 1 }), Some((8, 9)));
+        assert_eq!(char_span(src, Location { line: 2, column: 6 }), None);
+        assert_eq!(char_span(src, Location { line: 4, column: 1 }), None);
+        assert_eq!(char_span(src, Location { line: 0, column: 1 }), None);
+    }
+}
@@ -0,0 +1,65 @@
+import unittest
+from pathlib import Path
+
+import web_fmt
+
+# The module formats like the wasm package, so it shares its snapshots.
+test_root = Path(__file__).parent.parent.parent / "web_fmt" / "test_data"
+
+cases = [
+    path
+    for pattern in ("*.json", "*.tsx", "*.vue", "*.html")
+    for path in sorted(test_root.rglob(pattern))
+    if not path.name.startswith(".")
+]
+
+
+class TestWebFmt(unittest.TestCase):
+    def test_snapshots(self):
+        for path in cases:
+            with self.subTest(path.name):
+                expected = path.with_name(path.name + ".snap").read_text(encoding="utf-8")
+                actual = web_fmt.format(path.read_text(encoding="utf-8"), path.name)
+                self.assertEqual(actual, expected)
+
+    def test_check(self):
+        result = web_fmt.check('{"a":1}', "a.json")
+        self.assertFalse(result)
+        self.assertEqual(result.first_difference, (1, 2))
+
+        self.assertTrue(web_fmt.check('{ "a": 1 }\n', "a.json"))
+
+    def test_format_batch(self):
+        files = [(path.name, path.read_text(encoding="utf-8")) for path in cases]
+        files.append(("broken.xyz", "x"))
+
+        results = web_fmt.format_batch(files)
+
+        for path, result in zip(cases, results):
+            self.assertEqual(result, path.with_name(path.name + ".snap").read_text(encoding="utf-8"))
+        self.assertIsInstance(results[-1], web_fmt.FormatError)
+
+    def test_format_error(self):
+        with self.assertRaises(web_fmt.FormatError) as caught:
+            web_fmt.format('{"a":', "a.json")
+
+        error = caught.exception
+        self.assertIsInstance(error, ValueError)
+        self.assertEqual(error.language, "json")
+        self.assertEqual(error.message, str(error))
+
+        with self.assertRaises(web_fmt.FormatError) as caught:
+            web_fmt.format("x", "a.xyz")
+
+        self.assertIsNone(caught.exception.language)
+        self.assertIsNone(caught.exception.span)
+
+    def test_formatter(self):
+        formatter = web_fmt.Formatter({"layout": {"indentStyle": "space", "indentWidth": 4}})
+
+        self.assertEqual(formatter.format('{"a":1}', "a.json"), '{ "a": 1 }\n')
+        self.assertEqual(formatter.format("query{a}", "a.graphql"), "query {\n    a\n}\n")
+
+
+if __name__ == "__main__":
+    unittest.main()
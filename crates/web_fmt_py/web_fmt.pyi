@@ -0,0 +1,63 @@
+"""Formatter for web development (HTML/CSS/JS/JSON/GraphQL), with the config of `@wasm-fmt/web_fmt`."""
+
+from collections.abc import Sequence
+from typing import Any, Literal, final
+
+__version__: str
+
+Language = Literal["script", "style", "markup", "json", "graphql"]
+
+Config = dict[str, Any]
+"""The config of every language, with the keys of the JavaScript config, e.g.
+`{"layout": {"indentWidth": 4}, "script": {"quoteStyle": "single"}}`."""
+
+class FormatError(ValueError):
+    """Raised when code fails to format."""
+
+    language: Language | None
+    """The language of the file, or `None` when its extension is unsupported."""
+    span: tuple[int, int] | None
+    """The `(start, end)` character offsets of the source the error points at, if any."""
+    message: str
+    """The error of the formatter."""
+
+@final
+class CheckResult:
+    """Result of checking whether code is already formatted. It is truthy when the code is."""
+
+    formatted: bool
+    first_difference: tuple[int, int] | None
+    """The 1-based line and column where the source first differs from the formatted code."""
+
+    def __bool__(self) -> bool: ...
+
+@final
+class Formatter:
+    """A formatter holding its configuration, resolved once for every language."""
+
+    def __init__(self, config: Config | None = None) -> None: ...
+    def format(self, src: str, filename: str) -> str:
+        """Formats the given code based on the file extension."""
+    def check(self, src: str, filename: str) -> CheckResult:
+        """Checks whether the given code is already formatted, based on the file extension."""
+    def format_batch(self, files: Sequence[tuple[str, str]]) -> list[str | FormatError]:
+        """Formats many `(filename, source)` files in parallel.
+
+        Returns the formatted code of each file, in order, or the `FormatError` of the files
+        that failed to format, so that one broken file does not abort the batch.
+        """
+
+def format(src: str, filename: str, config: Config | None = None) -> str:
+    """Formats the given code based on the file extension with the provided configuration."""
+
+def check(src: str, filename: str, config: Config | None = None) -> CheckResult:
+    """Checks whether the given code is already formatted, based on the file extension."""
+
+def format_batch(files: Sequence[tuple[str, str]], config: Config | None = None) -> list[str | FormatError]:
+    """Formats many `(filename, source)` files in parallel, sharing the configuration across files."""
+
+def languages() -> list[Language]:
+    """Lists the languages compiled in."""
+
+def language_of(filename: str) -> Language:
+    """Returns the language of a file, whether or not it is compiled in."""
@@ -26,9 +26,12 @@ for (const packageJsonPath of globSync("*/package.json", { cwd: cratesDir })) {
 	packJson.version = version;
 	writeJSON(packageJsonFullPath, packJson);
 
-	const jsr = readJSON(jsrPath);
-	jsr.version = version;
-	writeJSON(jsrPath, jsr);
+	// Native addons are published to npm only.
+	if (fs.existsSync(jsrPath)) {
+		const jsr = readJSON(jsrPath);
+		jsr.version = version;
+		writeJSON(jsrPath, jsr);
+	}
 }
 
 function readJSON(filePath) {